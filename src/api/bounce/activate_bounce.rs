@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::bounce::{BounceId, BounceInfo};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -23,11 +23,16 @@ pub struct ActivateBounceResponse {
 impl Endpoint for ActivateBounceRequest {
     type Request = ActivateBounceRequest;
     type Response = ActivateBounceResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/bounces/{}/activate", self.bounce_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/bounces/{id}/activate".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,4 +1,4 @@
-use crate::Endpoint;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
@@ -8,6 +8,7 @@ pub struct GetDeliveryStatsRequest {}
 impl Endpoint for GetDeliveryStatsRequest {
     type Request = ();
     type Response = GetDeliveryStatsResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/deliverystats".into()
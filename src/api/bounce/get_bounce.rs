@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::bounce::{BounceId, BounceInfo};
 use crate::api::endpoint_with_path_segment;
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -17,11 +17,16 @@ pub struct GetBounceRequest {
 impl Endpoint for GetBounceRequest {
     type Request = GetBounceRequest;
     type Response = BounceInfo;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/bounces", &self.bounce_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/bounces/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::bounce::BounceId;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -22,11 +22,16 @@ pub struct GetBounceDumpResponse {
 impl Endpoint for GetBounceDumpRequest {
     type Request = GetBounceDumpRequest;
     type Response = GetBounceDumpResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/bounces/{}/dump", self.bounce_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/bounces/{id}/dump".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,5 +1,6 @@
-use crate::Endpoint;
 use crate::api::endpoint_with_query;
+use crate::paginate::{Page, Paginated, SEARCH_WINDOW, TimeSliced};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use typed_builder::TypedBuilder;
@@ -42,11 +43,14 @@ pub struct ListBouncesWithFiltersEntry {
     #[serde(rename = "Type")]
     pub type_field: Option<String>,
     pub email: String,
+    #[serde(rename = "MessageID", default)]
+    pub message_id: Option<String>,
 }
 
 impl Endpoint for ListBouncesWithFiltersRequest {
     type Request = ListBouncesWithFiltersRequest;
     type Response = ListBouncesWithFiltersResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -94,6 +98,47 @@ impl Endpoint for ListBouncesWithFiltersRequest {
     }
 }
 
+impl Paginated for ListBouncesWithFiltersRequest {
+    type Item = ListBouncesWithFiltersEntry;
+
+    const WINDOW: Option<i64> = Some(SEARCH_WINDOW);
+
+    fn offset(&self) -> i64 {
+        self.offset.unwrap_or_default()
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self {
+            count: Some(count),
+            offset: Some(offset),
+            ..self.clone()
+        }
+    }
+
+    fn into_page(response: ListBouncesWithFiltersResponse) -> Page<ListBouncesWithFiltersEntry> {
+        Page {
+            total_count: response.total_count,
+            items: response.bounces,
+        }
+    }
+}
+
+impl TimeSliced for ListBouncesWithFiltersRequest {
+    fn between(&self, from_date: String, to_date: String) -> Self {
+        Self {
+            from_date: Some(from_date),
+            to_date: Some(to_date),
+            ..self.clone()
+        }
+    }
+
+    /// The bounce, as a message bouncing for several recipients has several
+    /// bounces.
+    fn item_id(item: &ListBouncesWithFiltersEntry) -> Cow<'_, str> {
+        Cow::Owned(item.bounce_id.to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
@@ -168,4 +213,20 @@ mod tests {
         assert!(endpoint.contains("fromdate=2024-01-01"));
         assert!(endpoint.contains("todate=2024-02-01"));
     }
+
+    #[test]
+    fn bounces_of_one_message_have_their_own_ids() {
+        let bounce = |id| ListBouncesWithFiltersEntry {
+            bounce_id: id,
+            type_field: Some(String::from("HardBounce")),
+            email: format!("{id}@example.com"),
+            message_id: Some(String::from("msg-1")),
+        };
+
+        let (first, second) = (bounce(1), bounce(2));
+        assert_ne!(
+            ListBouncesWithFiltersRequest::item_id(&first),
+            ListBouncesWithFiltersRequest::item_id(&second)
+        );
+    }
 }
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::bounce::BounceInfo;
 use crate::api::{DEFAULT_PAGE_COUNT, DEFAULT_PAGE_OFFSET, endpoint_with_query};
+use crate::paginate::{Page, Paginated, SEARCH_WINDOW};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -28,6 +29,7 @@ pub struct ListBouncesResponse {
 impl Endpoint for ListBouncesRequest {
     type Request = ListBouncesRequest;
     type Response = ListBouncesResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -45,6 +47,27 @@ impl Endpoint for ListBouncesRequest {
     }
 }
 
+impl Paginated for ListBouncesRequest {
+    type Item = BounceInfo;
+
+    const WINDOW: Option<i64> = Some(SEARCH_WINDOW);
+
+    fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self { count, offset }
+    }
+
+    fn into_page(response: ListBouncesResponse) -> Page<BounceInfo> {
+        Page {
+            total_count: response.total_count,
+            items: response.bounces,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
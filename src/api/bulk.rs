@@ -1,7 +1,49 @@
 //! Bulk email API endpoints.
 
+use serde::{Deserialize, Serialize};
+
 mod get_bulk_status;
 mod send_bulk;
+#[cfg(feature = "bulk-wait")]
+mod wait_for_bulk;
 
 pub use get_bulk_status::*;
 pub use send_bulk::*;
+#[cfg(feature = "bulk-wait")]
+pub use wait_for_bulk::*;
+
+/// Status of a bulk send.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum BulkStatus {
+    /// The request was accepted and is waiting to be processed.
+    Accepted,
+    /// Messages are being sent.
+    Processing,
+    /// Every message was processed.
+    Completed,
+    /// The bulk send stopped before every message was processed.
+    Failed,
+    /// The bulk send was cancelled before every message was processed.
+    Cancelled,
+    /// Catch-all for any status not yet represented in this enum.
+    #[serde(untagged)]
+    Unknown(String),
+}
+
+impl BulkStatus {
+    /// Whether the bulk send is over, so its status will not change anymore.
+    ///
+    /// Unknown statuses are not considered terminal.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            BulkStatus::Completed | BulkStatus::Failed | BulkStatus::Cancelled
+        )
+    }
+
+    /// Whether the bulk send is over without processing every message.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, BulkStatus::Failed | BulkStatus::Cancelled)
+    }
+}
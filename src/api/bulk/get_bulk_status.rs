@@ -1,7 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
+use crate::api::bulk::BulkStatus;
 use crate::api::endpoint_with_path_segment;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -20,18 +21,23 @@ pub struct GetBulkStatusResponse {
     pub submitted_at: String,
     pub total_messages: i64,
     pub percentage_completed: f64,
-    pub status: String,
+    pub status: BulkStatus,
     pub subject: Option<String>,
 }
 
 impl Endpoint for GetBulkStatusRequest {
     type Request = GetBulkStatusRequest;
     type Response = GetBulkStatusResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/email/bulk", &self.bulk_request_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/email/bulk/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -78,6 +84,6 @@ mod tests {
             .bulk_request_id("dc5e5d98-c073-4c97-8ee5-f897dfd28b47".to_string())
             .build();
         let resp = req.execute(&client).await.expect("json decode");
-        assert_eq!(resp.status, "Completed");
+        assert_eq!(resp.status, BulkStatus::Completed);
     }
 }
@@ -1,9 +1,10 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 
-use crate::Endpoint;
+use crate::api::bulk::BulkStatus;
 use crate::api::email::{Attachment, Header, TrackLink};
 use crate::api::templates::TemplateId;
+use crate::{Endpoint, PostmarkErrorCode, ServerToken};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use typed_builder::TypedBuilder;
@@ -79,11 +80,11 @@ pub struct SendBulkEmailResponse {
     #[serde(rename = "ID", default, skip_serializing_if = "Option::is_none")]
     pub id: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub status: Option<String>,
+    pub status: Option<BulkStatus>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub submitted_at: Option<String>,
     #[serde(default)]
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -93,18 +94,23 @@ pub struct SendBulkEmailResponse {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct BulkEmailFieldError {
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     pub message: String,
 }
 
 impl Endpoint for SendBulkEmailRequest {
     type Request = SendBulkEmailRequest;
     type Response = SendBulkEmailResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/email/bulk".into()
     }
 
+    fn message_stream(&self) -> Option<Cow<'_, str>> {
+        self.message_stream.as_deref().map(Cow::Borrowed)
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -152,7 +158,7 @@ mod tests {
             .build();
 
         let resp = req.execute(&client).await.expect("json decode");
-        assert_eq!(resp.status.as_deref(), Some("Accepted"));
+        assert_eq!(resp.status, Some(BulkStatus::Accepted));
         assert_eq!(resp.error_code, 0);
     }
 
@@ -0,0 +1,279 @@
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::time::Duration;
+
+use futures_core::Stream;
+use futures_core::future::BoxFuture;
+use thiserror::Error;
+use tokio::time::{Instant, sleep, timeout_at};
+
+use crate::api::bulk::{BulkStatus, GetBulkStatusRequest, GetBulkStatusResponse};
+use crate::{Client, Query, QueryError};
+
+/// How long [`wait_for_bulk`] waits between two status requests by default.
+pub const DEFAULT_BULK_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long [`wait_for_bulk`] waits for a bulk send to complete by default.
+pub const DEFAULT_BULK_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Error, Debug)]
+pub enum WaitForBulkError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// A status request failed.
+    #[error("{}", source)]
+    Query { source: QueryError<E> },
+    /// The bulk send did not complete in time.
+    #[error("bulk send not completed after {timeout:?}")]
+    Timeout { timeout: Duration },
+}
+
+type StatusResult<E> = Result<GetBulkStatusResponse, WaitForBulkError<E>>;
+
+/// A status request, answering `None` once the deadline passed.
+type StatusRequest<'a, E> = BoxFuture<'a, Option<Result<GetBulkStatusResponse, QueryError<E>>>>;
+
+/// Follow the bulk send `id`, as returned in
+/// [`SendBulkEmailResponse::id`](crate::api::bulk::SendBulkEmailResponse::id).
+///
+/// The returned [`Stream`] requests the status every
+/// [`DEFAULT_BULK_POLL_INTERVAL`] and yields it whenever its status or
+/// `percentage_completed` changed. It ends after yielding a
+/// [terminal](BulkStatus::is_terminal) status, or an error. A send that
+/// [failed](BulkStatus::is_failure) ends with its status, not an error. It
+/// fails with [`WaitForBulkError::Timeout`] when the send is not over after
+/// [`DEFAULT_BULK_TIMEOUT`].
+///
+/// ```
+/// # use postmark::reqwest::PostmarkClient;
+/// use futures_util::TryStreamExt;
+/// use postmark::api::bulk::wait_for_bulk;
+/// use std::time::Duration;
+///
+/// # async fn f(client: &PostmarkClient, id: String) -> Result<(), Box<dyn std::error::Error>> {
+/// let mut progress = wait_for_bulk(client, id).interval(Duration::from_secs(10));
+/// while let Some(status) = progress.try_next().await? {
+///     println!("{:?}: {} completed", status.status, status.percentage_completed);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn wait_for_bulk<C>(client: &C, id: impl Into<String>) -> WaitForBulk<'_, C>
+where
+    C: Client + Send + Sync,
+{
+    WaitForBulk {
+        client,
+        request: GetBulkStatusRequest::builder()
+            .bulk_request_id(id.into())
+            .build(),
+        interval: DEFAULT_BULK_POLL_INTERVAL,
+        timeout: DEFAULT_BULK_TIMEOUT,
+        deadline: None,
+        last: None,
+        polled: false,
+        pending: None,
+        done: false,
+    }
+}
+
+/// The [`Stream`] of status updates returned by [`wait_for_bulk`].
+pub struct WaitForBulk<'a, C>
+where
+    C: Client,
+{
+    client: &'a C,
+    request: GetBulkStatusRequest,
+    interval: Duration,
+    timeout: Duration,
+    /// Set when the stream is first polled.
+    deadline: Option<Instant>,
+    last: Option<(BulkStatus, f64)>,
+    polled: bool,
+    pending: Option<StatusRequest<'a, C::Error>>,
+    done: bool,
+}
+
+impl<C> WaitForBulk<'_, C>
+where
+    C: Client + Send + Sync,
+{
+    /// Wait `interval` between two status requests.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Give up once the send is not over after `timeout`, counted from the
+    /// first poll of the stream.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Wait for the send to be over, and return its final status.
+    pub async fn completion(mut self) -> StatusResult<C::Error> {
+        let mut last = None;
+        while let Some(status) = std::future::poll_fn(|cx| Pin::new(&mut self).poll_next(cx)).await
+        {
+            last = Some(status?);
+        }
+        Ok(last.expect("the stream ends with a terminal status or an error"))
+    }
+}
+
+// The status request is boxed and nothing else is ever pinned.
+impl<C> Unpin for WaitForBulk<'_, C> where C: Client {}
+
+impl<C> Stream for WaitForBulk<'_, C>
+where
+    C: Client + Send + Sync,
+{
+    type Item = StatusResult<C::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            let deadline = *this
+                .deadline
+                .get_or_insert_with(|| Instant::now() + this.timeout);
+            let pending = this.pending.get_or_insert_with(|| {
+                let delay = if this.polled {
+                    this.interval
+                } else {
+                    Duration::ZERO
+                };
+                let request = this.request.clone();
+                let client = this.client;
+                Box::pin(async move {
+                    timeout_at(deadline, async move {
+                        sleep(delay).await;
+                        request.execute(client).await
+                    })
+                    .await
+                    .ok()
+                })
+            });
+
+            let result = ready!(pending.as_mut().poll(cx));
+            this.pending = None;
+            this.polled = true;
+
+            let status = match result {
+                Some(Ok(status)) => status,
+                Some(Err(source)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(WaitForBulkError::Query { source })));
+                }
+                None => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(WaitForBulkError::Timeout {
+                        timeout: this.timeout,
+                    })));
+                }
+            };
+
+            if status.status.is_terminal() {
+                this.done = true;
+                return Poll::Ready(Some(Ok(status)));
+            }
+            let progress = (status.status.clone(), status.percentage_completed);
+            if this.last.as_ref() != Some(&progress) {
+                this.last = Some(progress);
+                return Poll::Ready(Some(Ok(status)));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::TryStreamExt;
+    use http::Method;
+    use serde_json::json;
+
+    use super::*;
+    use crate::testing::{MockClient, MockResponse};
+
+    const PATH: &str = "/email/bulk/f2d1c3a4";
+
+    fn status(status: &str, percentage: f64) -> MockResponse {
+        MockResponse::ok(json!({
+            "ID": "f2d1c3a4",
+            "SubmittedAt": "2024-07-22T15:39:49Z",
+            "TotalMessages": 100,
+            "PercentageCompleted": percentage,
+            "Status": status,
+            "Subject": null,
+        }))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn yields_progress_until_completed() {
+        let client = MockClient::new();
+        client
+            .respond(Method::GET, PATH, status("Accepted", 0.0))
+            .respond(Method::GET, PATH, status("Processing", 0.5))
+            .respond(Method::GET, PATH, status("Processing", 0.5))
+            .respond(Method::GET, PATH, status("Completed", 1.0));
+
+        let start = Instant::now();
+        let updates: Vec<_> = wait_for_bulk(&client, "f2d1c3a4")
+            .interval(Duration::from_secs(2))
+            .try_collect()
+            .await
+            .expect("updates");
+
+        let progress: Vec<_> = updates
+            .iter()
+            .map(|update| (update.status.clone(), update.percentage_completed))
+            .collect();
+        assert_eq!(
+            progress,
+            [
+                (BulkStatus::Accepted, 0.0),
+                (BulkStatus::Processing, 0.5),
+                (BulkStatus::Completed, 1.0),
+            ]
+        );
+        assert_eq!(client.requests().len(), 4);
+        assert_eq!(start.elapsed(), Duration::from_secs(6));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn completion_ends_on_failure() {
+        let client = MockClient::new();
+        client
+            .respond(Method::GET, PATH, status("Processing", 0.2))
+            .respond(Method::GET, PATH, status("Failed", 0.4));
+
+        let status = wait_for_bulk(&client, "f2d1c3a4")
+            .completion()
+            .await
+            .expect("status");
+
+        assert_eq!(status.status, BulkStatus::Failed);
+        assert!(status.status.is_failure());
+        assert_eq!(client.requests().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn completion_times_out() {
+        let client = MockClient::new();
+        client.respond(Method::GET, PATH, status("Processing", 0.1));
+
+        let error = wait_for_bulk(&client, "f2d1c3a4")
+            .timeout(Duration::from_secs(58))
+            .completion()
+            .await
+            .expect_err("timeout");
+
+        assert!(matches!(error, WaitForBulkError::Timeout { .. }));
+        assert_eq!(client.requests().len(), 12);
+    }
+}
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::data_removal::DataRemovalId;
+use crate::{AccountToken, Endpoint};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -24,6 +24,7 @@ pub struct DataRemovalStatusResponse {
 impl Endpoint for CreateDataRemovalRequest {
     type Request = CreateDataRemovalRequest;
     type Response = DataRemovalStatusResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/data-removals".into()
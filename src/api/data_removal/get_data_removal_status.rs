@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::data_removal::{DataRemovalId, DataRemovalStatusResponse};
 use crate::api::endpoint_with_path_segment;
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -17,11 +17,16 @@ pub struct GetDataRemovalStatusRequest {
 impl Endpoint for GetDataRemovalStatusRequest {
     type Request = GetDataRemovalStatusRequest;
     type Response = DataRemovalStatusResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/data-removals", &self.data_removal_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/data-removals/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
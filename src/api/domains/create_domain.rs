@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::DomainDetails;
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -31,6 +31,7 @@ pub struct CreateDomainRequest {
 impl Endpoint for CreateDomainRequest {
     type Request = CreateDomainRequest;
     type Response = DomainDetails;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/domains".into()
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::DomainId;
 use crate::api::endpoint_with_path_segment;
+use crate::{AccountToken, Endpoint, PostmarkErrorCode};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -32,7 +32,7 @@ pub struct DeleteDomainRequest {
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteDomainResponse {
     /// [API Error codes](https://postmarkapp.com/developer/api/overview#error-codes)
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     /// Associated success or error message.
     pub message: String,
 }
@@ -40,11 +40,16 @@ pub struct DeleteDomainResponse {
 impl Endpoint for DeleteDomainRequest {
     type Request = DeleteDomainRequest;
     type Response = DeleteDomainResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/domains", &self.domain_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/domains/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
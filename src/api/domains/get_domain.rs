@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::{DomainDetails, DomainId};
 use crate::api::endpoint_with_path_segment;
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -27,11 +27,16 @@ pub struct GetDomainRequest {
 impl Endpoint for GetDomainRequest {
     type Request = GetDomainRequest;
     type Response = DomainDetails;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/domains", &self.domain_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/domains/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::DomainSummary;
 use crate::api::{DEFAULT_PAGE_COUNT, DEFAULT_PAGE_OFFSET, endpoint_with_query};
+use crate::paginate::{Page, Paginated};
+use crate::{AccountToken, Endpoint};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -43,6 +44,7 @@ pub struct ListDomainsResponse {
 impl Endpoint for ListDomainsRequest {
     type Request = ListDomainsRequest;
     type Response = ListDomainsResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -60,6 +62,25 @@ impl Endpoint for ListDomainsRequest {
     }
 }
 
+impl Paginated for ListDomainsRequest {
+    type Item = DomainSummary;
+
+    fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self { count, offset }
+    }
+
+    fn into_page(response: ListDomainsResponse) -> Page<DomainSummary> {
+        Page {
+            total_count: response.total_count,
+            items: response.domains,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::{DkimUpdateStatus, DomainId};
+use crate::{AccountToken, Endpoint};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -67,11 +67,16 @@ pub struct RotateDkimResponse {
 impl Endpoint for RotateDkimRequest {
     type Request = RotateDkimRequest;
     type Response = RotateDkimResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/domains/{}/rotatedkim", self.domain_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/domains/{id}/rotatedkim".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::{DomainDetails, DomainId};
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -26,11 +26,16 @@ pub struct VerifyDkimRequest {
 impl Endpoint for VerifyDkimRequest {
     type Request = VerifyDkimRequest;
     type Response = DomainDetails;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/domains/{}/verifyDkim", self.domain_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/domains/{id}/verifyDkim".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
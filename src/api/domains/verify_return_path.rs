@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::{DomainDetails, DomainId};
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -26,11 +26,16 @@ pub struct VerifyReturnPathRequest {
 impl Endpoint for VerifyReturnPathRequest {
     type Request = VerifyReturnPathRequest;
     type Response = DomainDetails;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/domains/{}/verifyReturnPath", self.domain_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/domains/{id}/verifyReturnPath".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
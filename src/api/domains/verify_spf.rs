@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::domains::DomainId;
+use crate::{AccountToken, Endpoint};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -41,11 +41,16 @@ pub struct VerifySpfResponse {
 impl Endpoint for VerifySpfRequest {
     type Request = VerifySpfRequest;
     type Response = VerifySpfResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/domains/{}/verifyspf", self.domain_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/domains/{id}/verifyspf".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
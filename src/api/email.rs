@@ -8,3 +8,19 @@ pub use send_email::*;
 pub use send_email_batch::*;
 pub use send_email_batch_with_templates::*;
 pub use send_email_with_template::*;
+
+use std::borrow::Cow;
+
+/// The message stream Postmark sends a message through when it names none.
+pub const DEFAULT_MESSAGE_STREAM: &str = "outbound";
+
+/// The message stream of a batch, when all its messages share one.
+fn shared_message_stream<'a>(
+    streams: impl Iterator<Item = Option<&'a str>>,
+) -> Option<Cow<'a, str>> {
+    let mut streams = streams.map(|stream| stream.unwrap_or(DEFAULT_MESSAGE_STREAM));
+    let first = streams.next()?;
+    streams
+        .all(|stream| stream == first)
+        .then_some(Cow::Borrowed(first))
+}
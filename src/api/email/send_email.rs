@@ -1,4 +1,4 @@
-use crate::{Endpoint, api::Body};
+use crate::{Endpoint, PostmarkErrorCode, ServerToken, api::Body};
 use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, collections::HashMap};
 use typed_builder::TypedBuilder;
@@ -128,13 +128,13 @@ pub struct SendEmailResponse {
     pub submitted_at: Option<String>,
     #[serde(rename = "MessageID")]
     pub message_id: Option<String>,
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     pub message: String,
 }
 
 impl SendEmailResponse {
     pub fn error_for_status(self) -> Result<Self, SendEmailResponse> {
-        if self.error_code == 0 {
+        if self.error_code.is_ok() {
             Ok(self)
         } else {
             Err(self)
@@ -145,11 +145,19 @@ impl SendEmailResponse {
 impl Endpoint for SendEmailRequest {
     type Request = SendEmailRequest;
     type Response = SendEmailResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/email".into()
     }
 
+    fn message_stream(&self) -> Option<Cow<'_, str>> {
+        let stream = self.message_stream.as_deref();
+        Some(Cow::Borrowed(
+            stream.unwrap_or(super::DEFAULT_MESSAGE_STREAM),
+        ))
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
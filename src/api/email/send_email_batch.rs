@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
 use super::send_email::{SendEmailRequest, SendEmailResponse};
-use crate::Endpoint;
+use crate::{Endpoint, ServerToken};
 
 /// Send multiple emails at once
 pub type SendEmailBatchRequest = Vec<SendEmailRequest>;
@@ -11,11 +11,16 @@ pub type SendEmailBatchResponse = Vec<SendEmailResponse>;
 impl Endpoint for SendEmailBatchRequest {
     type Request = SendEmailBatchRequest;
     type Response = SendEmailBatchResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/email/batch".into()
     }
 
+    fn message_stream(&self) -> Option<Cow<'_, str>> {
+        super::shared_message_stream(self.iter().map(|m| m.message_stream.as_deref()))
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::send_email_batch::SendEmailBatchResponse;
 use super::send_email_with_template::SendEmailWithTemplateRequest;
-use crate::Endpoint;
+use crate::{Endpoint, ServerToken};
 
 /// Send multiple emails at once
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
@@ -16,11 +16,16 @@ pub struct SendEmailBatchWithTemplatesRequest {
 impl Endpoint for SendEmailBatchWithTemplatesRequest {
     type Request = SendEmailBatchWithTemplatesRequest;
     type Response = SendEmailBatchResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/email/batchWithTemplates".into()
     }
 
+    fn message_stream(&self) -> Option<Cow<'_, str>> {
+        super::shared_message_stream(self.messages.iter().map(|m| m.message_stream.as_deref()))
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,4 +1,4 @@
-use crate::Endpoint;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use std::{
     borrow::Cow,
@@ -172,11 +172,19 @@ pub struct SendEmailWithTemplateRequest {
 impl Endpoint for SendEmailWithTemplateRequest {
     type Request = SendEmailWithTemplateRequest;
     type Response = SendEmailResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/email/withTemplate".into()
     }
 
+    fn message_stream(&self) -> Option<Cow<'_, str>> {
+        let stream = self.message_stream.as_deref();
+        Some(Cow::Borrowed(
+            stream.unwrap_or(super::DEFAULT_MESSAGE_STREAM),
+        ))
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::message_streams::{MessageStreamServerId, StreamIdOrName};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -25,11 +25,16 @@ pub struct ArchiveMessageStreamResponse {
 impl Endpoint for ArchiveMessageStreamRequest {
     type Request = ArchiveMessageStreamRequest;
     type Response = ArchiveMessageStreamResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/message-streams/{}/archive", self.stream_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/message-streams/{id}/archive".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,9 +1,9 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::message_streams::{
     MessageStream, MessageStreamType, SubscriptionManagementConfiguration,
 };
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -24,6 +24,7 @@ pub struct CreateMessageStreamRequest {
 impl Endpoint for CreateMessageStreamRequest {
     type Request = CreateMessageStreamRequest;
     type Response = MessageStream;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/message-streams".into()
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::message_streams::{Emails, StreamIdOrName, SuppressionCreateStatusType};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -30,11 +30,16 @@ pub struct CreateSuppression {
 impl Endpoint for CreateSuppressionRequest {
     type Request = CreateSuppressionRequest;
     type Response = CreateSuppressionResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/message-streams/{}/suppressions", self.stream_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/message-streams/{id}/suppressions".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
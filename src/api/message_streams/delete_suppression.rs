@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::message_streams::{StreamIdOrName, SuppressionStatusType};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -37,11 +37,16 @@ pub struct Emails {
 impl Endpoint for DeleteSuppressionRequest {
     type Request = DeleteSuppressionRequest;
     type Response = DeleteSuppressionResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/message-streams/{}/suppressions/delete", self.stream_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/message-streams/{id}/suppressions/delete".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::message_streams::{MessageStream, StreamIdOrName};
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -16,11 +16,16 @@ pub struct GetMessageStreamRequest {
 impl Endpoint for GetMessageStreamRequest {
     type Request = GetMessageStreamRequest;
     type Response = MessageStream;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/message-streams", &self.stream_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/message-streams/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::message_streams::StreamIdOrName;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -34,11 +34,16 @@ pub struct GetSuppression {
 impl Endpoint for GetSuppressionRequest {
     type Request = GetSuppressionRequest;
     type Response = GetSuppressionResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/message-streams/{}/suppressions/dump", self.stream_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/message-streams/{id}/suppressions/dump".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
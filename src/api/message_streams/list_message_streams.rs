@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_query;
 use crate::api::message_streams::{MessageStream, MessageStreamType};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -28,6 +28,7 @@ pub struct ListMessageStreamsResponse {
 impl Endpoint for ListMessageStreamsRequest {
     type Request = ListMessageStreamsRequest;
     type Response = ListMessageStreamsResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
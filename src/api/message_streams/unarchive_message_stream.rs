@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::message_streams::{MessageStream, StreamIdOrName};
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -15,11 +15,16 @@ pub struct UnarchiveMessageStreamRequest {
 impl Endpoint for UnarchiveMessageStreamRequest {
     type Request = UnarchiveMessageStreamRequest;
     type Response = MessageStream;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/message-streams/{}/unarchive", self.stream_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/message-streams/{id}/unarchive".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,5 +1,6 @@
 //! You'll find in messages API related endpoints.
 
+use crate::PostmarkErrorCode;
 use serde::{Deserialize, Serialize};
 
 mod bypass_blocked_inbound;
@@ -100,7 +101,7 @@ pub struct MessageDump {
 pub struct MessageActionResponse {
     pub message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub error_code: Option<i64>,
+    pub error_code: Option<PostmarkErrorCode>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
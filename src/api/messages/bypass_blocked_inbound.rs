@@ -2,8 +2,8 @@ use std::borrow::Cow;
 
 use serde::Serialize;
 
-use crate::Endpoint;
 use crate::api::messages::MessageActionResponse;
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct BypassBlockedInboundRequest {
@@ -22,11 +22,16 @@ pub type BypassBlockedInboundResponse = MessageActionResponse;
 impl Endpoint for BypassBlockedInboundRequest {
     type Request = ();
     type Response = BypassBlockedInboundResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/messages/inbound/{}/bypass", self.message_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/messages/inbound/{id}/bypass".into()
+    }
+
     fn body(&self) -> &Self::Request {
         &()
     }
@@ -43,8 +48,8 @@ mod tests {
     use serde_json::json;
 
     use super::*;
-    use crate::Query;
     use crate::reqwest::PostmarkClient;
+    use crate::{PostmarkErrorCode, Query};
 
     #[tokio::test]
     async fn bypass_blocked_inbound_puts_bypass() {
@@ -69,6 +74,6 @@ mod tests {
             .expect("Should decode bypass response");
 
         assert_eq!(resp.message, "OK");
-        assert_eq!(resp.error_code, Some(0));
+        assert_eq!(resp.error_code, Some(PostmarkErrorCode::Ok));
     }
 }
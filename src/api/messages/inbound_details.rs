@@ -2,8 +2,8 @@ use std::borrow::Cow;
 
 use serde::Serialize;
 
-use crate::Endpoint;
 use crate::api::messages::InboundMessageDetails;
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct InboundDetailsRequest {
@@ -22,11 +22,16 @@ pub type InboundDetailsResponse = InboundMessageDetails;
 impl Endpoint for InboundDetailsRequest {
     type Request = ();
     type Response = InboundDetailsResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/messages/inbound/{}/details", self.message_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/messages/inbound/{id}/details".into()
+    }
+
     fn body(&self) -> &Self::Request {
         &()
     }
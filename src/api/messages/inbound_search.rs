@@ -4,9 +4,10 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_query;
 use crate::api::messages::MessageSummary;
+use crate::paginate::{Page, Paginated, SEARCH_WINDOW, TimeSliced};
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize, TypedBuilder)]
 #[builder(field_defaults(default, setter(strip_option)))]
@@ -22,6 +23,10 @@ pub struct InboundSearchRequest {
     pub subject: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_date: Option<String>,
 }
 
 impl Default for InboundSearchRequest {
@@ -41,6 +46,7 @@ pub struct InboundSearchResponse {
 impl Endpoint for InboundSearchRequest {
     type Request = InboundSearchRequest;
     type Response = InboundSearchResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -60,6 +66,12 @@ impl Endpoint for InboundSearchRequest {
         if let Some(ref to) = self.to {
             serializer.append_pair("to", to);
         }
+        if let Some(ref from_date) = self.from_date {
+            serializer.append_pair("fromdate", from_date);
+        }
+        if let Some(ref to_date) = self.to_date {
+            serializer.append_pair("todate", to_date);
+        }
 
         let query = serializer.finish();
         endpoint_with_query("/messages/inbound", query)
@@ -74,6 +86,45 @@ impl Endpoint for InboundSearchRequest {
     }
 }
 
+impl Paginated for InboundSearchRequest {
+    type Item = MessageSummary;
+
+    const WINDOW: Option<i64> = Some(SEARCH_WINDOW);
+
+    fn offset(&self) -> i64 {
+        self.offset.unwrap_or_default()
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self {
+            count: Some(count),
+            offset: Some(offset),
+            ..self.clone()
+        }
+    }
+
+    fn into_page(response: InboundSearchResponse) -> Page<MessageSummary> {
+        Page {
+            total_count: response.total_count,
+            items: response.messages,
+        }
+    }
+}
+
+impl TimeSliced for InboundSearchRequest {
+    fn between(&self, from_date: String, to_date: String) -> Self {
+        Self {
+            from_date: Some(from_date),
+            to_date: Some(to_date),
+            ..self.clone()
+        }
+    }
+
+    fn item_id(item: &MessageSummary) -> Cow<'_, str> {
+        Cow::Borrowed(&item.message_id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
@@ -145,6 +196,8 @@ mod tests {
             .mailbox_hash("box-1".to_string())
             .subject("Reply".to_string())
             .to("inbound@example.com".to_string())
+            .from_date("2024-01-01".to_string())
+            .to_date("2024-01-31T23:59:59".to_string())
             .build();
 
         let endpoint = req.endpoint();
@@ -156,5 +209,7 @@ mod tests {
         assert!(endpoint.contains("mailboxhash=box-1"));
         assert!(endpoint.contains("subject=Reply"));
         assert!(endpoint.contains("to=inbound%40example.com"));
+        assert!(endpoint.contains("fromdate=2024-01-01"));
+        assert!(endpoint.contains("todate=2024-01-31T23%3A59%3A59"));
     }
 }
@@ -4,9 +4,10 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_query;
 use crate::api::messages::MessageOpen;
+use crate::paginate::{Page, Paginated, SEARCH_WINDOW};
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize, TypedBuilder)]
 #[builder(field_defaults(default, setter(strip_option)))]
@@ -40,6 +41,7 @@ pub struct MessageOpensResponse {
 impl Endpoint for MessageOpensRequest {
     type Request = MessageOpensRequest;
     type Response = MessageOpensResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -72,6 +74,31 @@ impl Endpoint for MessageOpensRequest {
     }
 }
 
+impl Paginated for MessageOpensRequest {
+    type Item = MessageOpen;
+
+    const WINDOW: Option<i64> = Some(SEARCH_WINDOW);
+
+    fn offset(&self) -> i64 {
+        self.offset.unwrap_or_default()
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self {
+            count: Some(count),
+            offset: Some(offset),
+            ..self.clone()
+        }
+    }
+
+    fn into_page(response: MessageOpensResponse) -> Page<MessageOpen> {
+        Page {
+            total_count: response.total_count,
+            items: response.opens,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
@@ -2,8 +2,8 @@ use std::borrow::Cow;
 
 use serde::Serialize;
 
-use crate::Endpoint;
 use crate::api::messages::OutboundMessageDetails;
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct OutboundDetailsRequest {
@@ -22,11 +22,16 @@ pub type OutboundDetailsResponse = OutboundMessageDetails;
 impl Endpoint for OutboundDetailsRequest {
     type Request = ();
     type Response = OutboundDetailsResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/messages/outbound/{}/details", self.message_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/messages/outbound/{id}/details".into()
+    }
+
     fn body(&self) -> &Self::Request {
         &()
     }
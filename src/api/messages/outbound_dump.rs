@@ -2,8 +2,8 @@ use std::borrow::Cow;
 
 use serde::Serialize;
 
-use crate::Endpoint;
 use crate::api::messages::MessageDump;
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct OutboundDumpRequest {
@@ -22,11 +22,16 @@ pub type OutboundDumpResponse = MessageDump;
 impl Endpoint for OutboundDumpRequest {
     type Request = ();
     type Response = OutboundDumpResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/messages/outbound/{}/dump", self.message_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/messages/outbound/{id}/dump".into()
+    }
+
     fn body(&self) -> &Self::Request {
         &()
     }
@@ -4,9 +4,10 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_query;
 use crate::api::messages::MessageSummary;
+use crate::paginate::{Page, Paginated, SEARCH_WINDOW, TimeSliced};
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize, TypedBuilder)]
 #[builder(field_defaults(default, setter(strip_option)))]
@@ -22,6 +23,10 @@ pub struct OutboundSearchRequest {
     pub tag: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message_stream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_date: Option<String>,
 }
 
 impl Default for OutboundSearchRequest {
@@ -40,6 +45,7 @@ pub struct OutboundSearchResponse {
 impl Endpoint for OutboundSearchRequest {
     type Request = OutboundSearchRequest;
     type Response = OutboundSearchResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -59,6 +65,12 @@ impl Endpoint for OutboundSearchRequest {
         if let Some(ref message_stream) = self.message_stream {
             serializer.append_pair("messagestream", message_stream);
         }
+        if let Some(ref from_date) = self.from_date {
+            serializer.append_pair("fromdate", from_date);
+        }
+        if let Some(ref to_date) = self.to_date {
+            serializer.append_pair("todate", to_date);
+        }
 
         endpoint_with_query("/messages/outbound", serializer.finish())
     }
@@ -72,6 +84,45 @@ impl Endpoint for OutboundSearchRequest {
     }
 }
 
+impl Paginated for OutboundSearchRequest {
+    type Item = MessageSummary;
+
+    const WINDOW: Option<i64> = Some(SEARCH_WINDOW);
+
+    fn offset(&self) -> i64 {
+        self.offset.unwrap_or_default()
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self {
+            count: Some(count),
+            offset: Some(offset),
+            ..self.clone()
+        }
+    }
+
+    fn into_page(response: OutboundSearchResponse) -> Page<MessageSummary> {
+        Page {
+            total_count: response.total_count,
+            items: response.messages,
+        }
+    }
+}
+
+impl TimeSliced for OutboundSearchRequest {
+    fn between(&self, from_date: String, to_date: String) -> Self {
+        Self {
+            from_date: Some(from_date),
+            to_date: Some(to_date),
+            ..self.clone()
+        }
+    }
+
+    fn item_id(item: &MessageSummary) -> Cow<'_, str> {
+        Cow::Borrowed(&item.message_id)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
@@ -125,6 +176,8 @@ mod tests {
             .recipient("user@example.com".to_string())
             .tag("welcome".to_string())
             .message_stream("outbound".to_string())
+            .from_date("2024-01-01".to_string())
+            .to_date("2024-01-31T23:59:59".to_string())
             .build();
 
         let endpoint = req.endpoint();
@@ -136,5 +189,7 @@ mod tests {
         assert!(endpoint.contains("recipient=user%40example.com"));
         assert!(endpoint.contains("tag=welcome"));
         assert!(endpoint.contains("messagestream=outbound"));
+        assert!(endpoint.contains("fromdate=2024-01-01"));
+        assert!(endpoint.contains("todate=2024-01-31T23%3A59%3A59"));
     }
 }
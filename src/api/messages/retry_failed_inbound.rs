@@ -2,8 +2,8 @@ use std::borrow::Cow;
 
 use serde::Serialize;
 
-use crate::Endpoint;
 use crate::api::messages::MessageActionResponse;
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct RetryFailedInboundRequest {
@@ -22,11 +22,16 @@ pub type RetryFailedInboundResponse = MessageActionResponse;
 impl Endpoint for RetryFailedInboundRequest {
     type Request = ();
     type Response = RetryFailedInboundResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/messages/inbound/{}/retry", self.message_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/messages/inbound/{id}/retry".into()
+    }
+
     fn body(&self) -> &Self::Request {
         &()
     }
@@ -43,8 +48,8 @@ mod tests {
     use serde_json::json;
 
     use super::*;
-    use crate::Query;
     use crate::reqwest::PostmarkClient;
+    use crate::{PostmarkErrorCode, Query};
 
     #[tokio::test]
     async fn retry_failed_inbound_puts_retry() {
@@ -69,6 +74,6 @@ mod tests {
             .expect("Should decode retry response");
 
         assert_eq!(resp.message, "OK");
-        assert_eq!(resp.error_code, Some(0));
+        assert_eq!(resp.error_code, Some(PostmarkErrorCode::Ok));
     }
 }
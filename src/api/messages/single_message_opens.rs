@@ -4,9 +4,9 @@ use serde::Serialize;
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
 
-use crate::Endpoint;
 use crate::api::messages::MessageOpensResponse;
 use crate::api::{endpoint_with_path_segment, endpoint_with_query};
+use crate::{Endpoint, ServerToken};
 
 #[derive(Debug, Clone, PartialEq, Serialize, TypedBuilder)]
 #[builder(field_defaults(default, setter(strip_option)))]
@@ -24,6 +24,7 @@ pub struct SingleMessageOpensRequest {
 impl Endpoint for SingleMessageOpensRequest {
     type Request = SingleMessageOpensRequest;
     type Response = MessageOpensResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -39,6 +40,10 @@ impl Endpoint for SingleMessageOpensRequest {
         endpoint_with_query(path.as_ref(), serializer.finish())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/messages/outbound/opens/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
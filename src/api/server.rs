@@ -21,7 +21,7 @@ mod get_current_server;
 mod get_server;
 mod list_servers;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ServerIdOrName {
     ServerId(ServerId),
     ServerName(String),
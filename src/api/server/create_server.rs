@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::server::{DeliveryType, Server, ServerColor};
+use crate::{AccountToken, Endpoint};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -24,6 +24,7 @@ pub struct CreateServerRequest {
 impl Endpoint for CreateServerRequest {
     type Request = CreateServerRequest;
     type Response = Server;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/servers".into()
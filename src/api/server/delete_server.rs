@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::server::ServerId;
+use crate::{AccountToken, Endpoint, PostmarkErrorCode};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -17,18 +17,23 @@ pub struct DeleteServerRequest {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteServerResponse {
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     pub message: String,
 }
 
 impl Endpoint for DeleteServerRequest {
     type Request = DeleteServerRequest;
     type Response = DeleteServerResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/servers", &self.server_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/servers/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
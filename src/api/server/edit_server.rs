@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::server::{DeliveryType, Server, ServerColor};
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -25,6 +25,7 @@ pub struct EditServerRequest {
 impl Endpoint for EditServerRequest {
     type Request = EditServerRequest;
     type Response = Server;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/server".into()
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::server::{DeliveryType, Server, ServerColor, ServerId};
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -29,11 +29,16 @@ pub struct EditServerByIdRequest {
 impl Endpoint for EditServerByIdRequest {
     type Request = EditServerByIdRequest;
     type Response = Server;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/servers", &self.server_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/servers/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
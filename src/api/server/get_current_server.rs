@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::server::Server;
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -12,6 +12,7 @@ pub struct GetCurrentServerRequest {}
 impl Endpoint for GetCurrentServerRequest {
     type Request = GetCurrentServerRequest;
     type Response = Server;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/server".into()
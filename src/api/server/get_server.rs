@@ -3,9 +3,9 @@ use std::borrow::Cow;
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::server::{Server, ServerIdOrName};
+use crate::{AccountToken, Endpoint};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -19,11 +19,16 @@ pub struct GetServerRequest {
 impl Endpoint for GetServerRequest {
     type Request = GetServerRequest;
     type Response = Server;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/servers", &self.server_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/servers/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::server::Server;
 use crate::api::{DEFAULT_PAGE_COUNT, DEFAULT_PAGE_OFFSET, endpoint_with_query};
+use crate::paginate::{Page, Paginated};
+use crate::{AccountToken, Endpoint};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -28,6 +29,7 @@ pub struct ListServersResponse {
 impl Endpoint for ListServersRequest {
     type Request = ListServersRequest;
     type Response = ListServersResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -45,6 +47,25 @@ impl Endpoint for ListServersRequest {
     }
 }
 
+impl Paginated for ListServersRequest {
+    type Item = Server;
+
+    fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self { count, offset }
+    }
+
+    fn into_page(response: ListServersResponse) -> Page<Server> {
+        Page {
+            total_count: response.total_count,
+            items: response.servers,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
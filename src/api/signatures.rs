@@ -1,10 +1,11 @@
 //! Sender signatures API endpoints.
 
+use crate::PostmarkErrorCode;
 use crate::api::types::id_type;
 use serde::{Deserialize, Serialize};
 
 id_type!(pub SignatureId);
-pub type ApiErrorCode = i64;
+pub type ApiErrorCode = PostmarkErrorCode;
 
 mod create_signature;
 mod delete_signature;
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::signatures::SenderSignature;
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -25,6 +25,7 @@ pub struct CreateSignatureRequest {
 impl Endpoint for CreateSignatureRequest {
     type Request = CreateSignatureRequest;
     type Response = SenderSignature;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/senders".into()
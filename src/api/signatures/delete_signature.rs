@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::signatures::{BasicApiResponse, SignatureId};
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -17,11 +17,16 @@ pub struct DeleteSignatureRequest {
 impl Endpoint for DeleteSignatureRequest {
     type Request = DeleteSignatureRequest;
     type Response = BasicApiResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/senders", &self.signature_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/senders/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
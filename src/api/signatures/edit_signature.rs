@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::signatures::{SenderSignature, SignatureId};
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -27,11 +27,16 @@ pub struct EditSignatureRequest {
 impl Endpoint for EditSignatureRequest {
     type Request = EditSignatureRequest;
     type Response = SenderSignature;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/senders", &self.signature_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/senders/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::signatures::SenderSignatureSummary;
 use crate::api::{DEFAULT_PAGE_COUNT, DEFAULT_PAGE_OFFSET, endpoint_with_query};
+use crate::paginate::{Page, Paginated};
+use crate::{AccountToken, Endpoint};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -28,6 +29,7 @@ pub struct ListSignaturesResponse {
 impl Endpoint for ListSignaturesRequest {
     type Request = ListSignaturesRequest;
     type Response = ListSignaturesResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -45,6 +47,25 @@ impl Endpoint for ListSignaturesRequest {
     }
 }
 
+impl Paginated for ListSignaturesRequest {
+    type Item = SenderSignatureSummary;
+
+    fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self { count, offset }
+    }
+
+    fn into_page(response: ListSignaturesResponse) -> Page<SenderSignatureSummary> {
+        Page {
+            total_count: response.total_count,
+            items: response.sender_signatures,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
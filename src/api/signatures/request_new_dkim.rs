@@ -3,8 +3,8 @@ use std::borrow::Cow;
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
-use crate::Endpoint;
 use crate::api::signatures::{SenderSignature, SignatureId};
+use crate::{AccountToken, Endpoint};
 
 #[derive(Debug, Clone, PartialEq, Serialize, TypedBuilder)]
 #[serde(rename_all = "PascalCase")]
@@ -19,11 +19,16 @@ pub type RequestNewDkimResponse = SenderSignature;
 impl Endpoint for RequestNewSignatureDkimRequest {
     type Request = ();
     type Response = RequestNewDkimResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/senders/{}/requestnewdkim", self.signature_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/senders/{id}/requestnewdkim".into()
+    }
+
     fn body(&self) -> &Self::Request {
         &()
     }
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::signatures::{BasicApiResponse, SignatureId};
+use crate::{AccountToken, Endpoint};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -16,11 +16,16 @@ pub struct ResendSignatureConfirmationRequest {
 impl Endpoint for ResendSignatureConfirmationRequest {
     type Request = ResendSignatureConfirmationRequest;
     type Response = BasicApiResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/senders/{}/resend", self.signature_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/senders/{id}/resend".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -3,8 +3,8 @@ use std::borrow::Cow;
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
-use crate::Endpoint;
 use crate::api::signatures::{SenderSignature, SignatureId};
+use crate::{AccountToken, Endpoint};
 
 #[derive(Debug, Clone, PartialEq, Serialize, TypedBuilder)]
 #[serde(rename_all = "PascalCase")]
@@ -19,11 +19,16 @@ pub type VerifySignatureSpfResponse = SenderSignature;
 impl Endpoint for VerifySignatureSpfRequest {
     type Request = ();
     type Response = VerifySignatureSpfResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format!("/senders/{}/verifyspf", self.signature_id).into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/senders/{id}/verifyspf".into()
+    }
+
     fn body(&self) -> &Self::Request {
         &()
     }
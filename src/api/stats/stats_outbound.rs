@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::stats::{StatsQuery, stats_endpoint};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use typed_builder::TypedBuilder;
@@ -39,6 +39,7 @@ pub struct OutboundOverviewResponse {
 impl Endpoint for GetOutboundOverviewRequest {
     type Request = GetOutboundOverviewRequest;
     type Response = OutboundOverviewResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         stats_endpoint("/stats/outbound", &self.query)
@@ -66,6 +67,7 @@ macro_rules! stats_req {
         impl Endpoint for $name {
             type Request = $name;
             type Response = $resp;
+            type Scope = ServerToken;
 
             fn endpoint(&self) -> Cow<'static, str> {
                 stats_endpoint($path, &self.query)
@@ -3,9 +3,9 @@ use std::borrow::Cow;
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
-use crate::Endpoint;
 use crate::api::server::ServerId;
 use crate::api::templates::{TemplateAction, TemplateId, TemplateType};
+use crate::{AccountToken, Endpoint};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "PascalCase")]
@@ -41,6 +41,7 @@ pub struct Template {
 impl Endpoint for CopyTemplatesRequest {
     type Request = CopyTemplatesRequest;
     type Response = CopyTemplatesResponse;
+    type Scope = AccountToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/templates/push".into()
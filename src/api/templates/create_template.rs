@@ -1,4 +1,4 @@
-use crate::{Endpoint, api::Body};
+use crate::{Endpoint, ServerToken, api::Body};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use typed_builder::TypedBuilder;
@@ -96,6 +96,7 @@ pub struct CreateTemplateResponse {
 impl Endpoint for CreateTemplateRequest {
     type Request = CreateTemplateRequest;
     type Response = CreateTemplateResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/templates".into()
@@ -1,4 +1,4 @@
-use crate::{Endpoint, api::endpoint_with_path_segment};
+use crate::{Endpoint, PostmarkErrorCode, ServerToken, api::endpoint_with_path_segment};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use typed_builder::TypedBuilder;
@@ -32,7 +32,7 @@ pub struct DeleteTemplateRequest {
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteTemplateResponse {
     /// [API Error codes]: https://postmarkapp.com/developer/api/overview#error-codes
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     /// Associated success or error message.
     pub message: String,
 }
@@ -40,11 +40,16 @@ pub struct DeleteTemplateResponse {
 impl Endpoint for DeleteTemplateRequest {
     type Request = DeleteTemplateRequest;
     type Response = DeleteTemplateResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/templates", &self.id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/templates/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,4 +1,4 @@
-use crate::{Endpoint, api::Body};
+use crate::{Endpoint, ServerToken, api::Body};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use typed_builder::TypedBuilder;
@@ -96,11 +96,16 @@ pub struct EditTemplateResponse {
 impl Endpoint for EditTemplateRequest {
     type Request = EditTemplateRequest;
     type Response = EditTemplateResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         format! {"/templates/{}", self.id}.into()
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/templates/{id}".into()
+    }
+
     fn method(&self) -> http::Method {
         http::Method::PUT
     }
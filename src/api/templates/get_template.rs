@@ -1,5 +1,5 @@
 use crate::{
-    Endpoint,
+    Endpoint, ServerToken,
     api::{Body, endpoint_with_path_segment},
 };
 use serde::{Deserialize, Serialize};
@@ -58,11 +58,16 @@ pub struct GetTemplateResponse {
 impl Endpoint for GetTemplateRequest {
     type Request = GetTemplateRequest;
     type Response = GetTemplateResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/templates", &self.id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/templates/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
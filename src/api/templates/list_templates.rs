@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::templates::{TemplateId, TemplateType};
 use crate::api::{DEFAULT_PAGE_COUNT, DEFAULT_PAGE_OFFSET, endpoint_with_query};
+use crate::paginate::{Page, Paginated};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -45,6 +46,7 @@ pub struct TemplateSummary {
 impl Endpoint for ListTemplatesRequest {
     type Request = ListTemplatesRequest;
     type Response = ListTemplatesResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -62,6 +64,25 @@ impl Endpoint for ListTemplatesRequest {
     }
 }
 
+impl Paginated for ListTemplatesRequest {
+    type Item = TemplateSummary;
+
+    fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self { count, offset }
+    }
+
+    fn into_page(response: ListTemplatesResponse) -> Page<TemplateSummary> {
+        Page {
+            total_count: response.total_count,
+            items: response.templates,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::templates::TemplateType;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use typed_builder::TypedBuilder;
@@ -56,6 +56,7 @@ pub struct ValidateTemplateError {
 impl Endpoint for ValidateTemplateRequest {
     type Request = ValidateTemplateRequest;
     type Response = ValidateTemplateResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/templates/validate".into()
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::triggers::InboundRule;
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -14,6 +14,7 @@ pub struct CreateInboundRuleTriggerRequest {
 impl Endpoint for CreateInboundRuleTriggerRequest {
     type Request = CreateInboundRuleTriggerRequest;
     type Response = InboundRule;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/triggers/inboundrules".into()
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::triggers::InboundRuleTriggerId;
+use crate::{Endpoint, PostmarkErrorCode, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -17,18 +17,23 @@ pub struct DeleteInboundRuleTriggerRequest {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteInboundRuleTriggerResponse {
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     pub message: String,
 }
 
 impl Endpoint for DeleteInboundRuleTriggerRequest {
     type Request = DeleteInboundRuleTriggerRequest;
     type Response = DeleteInboundRuleTriggerResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/triggers/inboundrules", &self.trigger_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/triggers/inboundrules/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
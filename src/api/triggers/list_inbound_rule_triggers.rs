@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::triggers::InboundRuleTriggerId;
 use crate::api::{DEFAULT_PAGE_COUNT, DEFAULT_PAGE_OFFSET, endpoint_with_query};
+use crate::paginate::{Page, Paginated};
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -36,6 +37,7 @@ pub struct ListInboundRuleTriggersResponse {
 impl Endpoint for ListInboundRuleTriggersRequest {
     type Request = ListInboundRuleTriggersRequest;
     type Response = ListInboundRuleTriggersResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
@@ -53,6 +55,25 @@ impl Endpoint for ListInboundRuleTriggersRequest {
     }
 }
 
+impl Paginated for ListInboundRuleTriggersRequest {
+    type Item = InboundRule;
+
+    fn offset(&self) -> i64 {
+        self.offset
+    }
+
+    fn page(&self, count: i64, offset: i64) -> Self {
+        Self { count, offset }
+    }
+
+    fn into_page(response: ListInboundRuleTriggersResponse) -> Page<InboundRule> {
+        Page {
+            total_count: response.total_count,
+            items: response.inbound_rules,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use httptest::matchers::request;
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::webhooks::WebhookId;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -37,6 +37,7 @@ pub struct SubscriptionChangeTriggerConfig {
 impl Endpoint for CreateWebhookRequest {
     type Request = CreateWebhookRequest;
     type Response = CreateWebhookResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         "/webhooks".into()
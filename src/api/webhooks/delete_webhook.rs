@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::webhooks::WebhookId;
+use crate::{Endpoint, PostmarkErrorCode, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
@@ -17,18 +17,23 @@ pub struct DeleteWebhookRequest {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "PascalCase")]
 pub struct DeleteWebhookResponse {
-    pub error_code: i64,
+    pub error_code: PostmarkErrorCode,
     pub message: String,
 }
 
 impl Endpoint for DeleteWebhookRequest {
     type Request = DeleteWebhookRequest;
     type Response = DeleteWebhookResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/webhooks", &self.webhook_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/webhooks/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::webhooks::{Webhook, WebhookHeader, WebhookHttpAuth, WebhookId, WebhookTriggers};
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -29,11 +29,16 @@ pub struct EditWebhookRequest {
 impl Endpoint for EditWebhookRequest {
     type Request = EditWebhookRequest;
     type Response = Webhook;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/webhooks", &self.webhook_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/webhooks/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_path_segment;
 use crate::api::webhooks::{Webhook, WebhookId};
+use crate::{Endpoint, ServerToken};
 use serde::Serialize;
 use typed_builder::TypedBuilder;
 
@@ -17,11 +17,16 @@ pub struct GetWebhookRequest {
 impl Endpoint for GetWebhookRequest {
     type Request = GetWebhookRequest;
     type Response = Webhook;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         endpoint_with_path_segment("/webhooks", &self.webhook_id.to_string())
     }
 
+    fn path_template(&self) -> Cow<'static, str> {
+        "/webhooks/{id}".into()
+    }
+
     fn body(&self) -> &Self::Request {
         self
     }
@@ -1,8 +1,8 @@
 use std::borrow::Cow;
 
-use crate::Endpoint;
 use crate::api::endpoint_with_query;
 use crate::api::webhooks::WebhookId;
+use crate::{Endpoint, ServerToken};
 use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 use url::form_urlencoded::Serializer;
@@ -81,6 +81,7 @@ pub struct IncludeContentTrigger {
 impl Endpoint for ListWebhooksRequest {
     type Request = ListWebhooksRequest;
     type Response = ListWebhooksResponse;
+    type Scope = ServerToken;
 
     fn endpoint(&self) -> Cow<'static, str> {
         let mut serializer = Serializer::new(String::new());
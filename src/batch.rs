@@ -0,0 +1,762 @@
+//! Sending any number of messages through the batch endpoints.
+//!
+//! Postmark takes at most [`MAX_BATCH_MESSAGES`] messages and
+//! [`MAX_BATCH_BYTES`] of JSON per batch. [`BatchSender`] splits messages
+//! into batches within both limits, sends a few batches at a time and
+//! returns one result per message, in the order the messages were given.
+//!
+//! [`BatchSender::execute`] pairs each message with what became of it in a
+//! [`BatchOutcome`], so the failed ones can be told apart and sent again.
+//! [`BatchOutcome::into_retryable`] only gives back messages Postmark did not
+//! take; those of a batch that failed without a clear answer, such as a
+//! timeout or a `500`, may have been sent and are listed by
+//! [`BatchOutcome::uncertain`] instead:
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! # use postmark::api::email::SendEmailRequest;
+//! use postmark::batch::BatchSender;
+//!
+//! # async fn f(emails: Vec<SendEmailRequest>) {
+//! let sender = BatchSender::new(PostmarkClient::default()).with_concurrency(8);
+//!
+//! let outcome = sender.execute(emails).await;
+//! for entry in outcome.failed() {
+//!     eprintln!("{}: {}", entry.input.to, entry.result.as_ref().unwrap_err());
+//! }
+//! let outcome = sender.execute(outcome.into_retryable()).await;
+//! # }
+//! ```
+
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::Poll;
+
+use http::StatusCode;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::api::email::{
+    SendEmailBatchResponse, SendEmailBatchWithTemplatesRequest, SendEmailRequest,
+    SendEmailResponse, SendEmailWithTemplateRequest,
+};
+use crate::client;
+use crate::{Client, Endpoint, PostmarkErrorCode, QueryError};
+
+/// The most messages Postmark takes in one batch.
+pub const MAX_BATCH_MESSAGES: usize = 500;
+
+/// The largest batch Postmark takes, in bytes of JSON.
+pub const MAX_BATCH_BYTES: usize = 50 * 1024 * 1024;
+
+/// How many batches [`BatchSender::new`] sends at a time.
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// The error for one message sent by a [`BatchSender`].
+#[derive(Error, Debug)]
+pub enum BatchError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// The batch carrying the message failed. Every message of the batch
+    /// shares the error.
+    #[error("{}", source)]
+    Query { source: Arc<QueryError<E>> },
+    /// The message alone is larger than a batch may be, so it was not sent.
+    #[error("message of {size} bytes does not fit in a batch of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+    /// Postmark answered the batch with fewer responses than messages.
+    #[error("the batch response has no entry for the message")]
+    MissingResponse,
+}
+
+impl<E> BatchError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Whether the message was not sent, and sending it again may succeed:
+    /// its batch was answered with a `429`, or an open circuit breaker kept
+    /// it from being sent.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            BatchError::Query { source } => match &**source {
+                QueryError::Api(error) => error.status == StatusCode::TOO_MANY_REQUESTS,
+                QueryError::CircuitOpen => true,
+                _ => false,
+            },
+            BatchError::TooLarge { .. } | BatchError::MissingResponse => false,
+        }
+    }
+
+    /// Whether the message may have been sent although its batch failed:
+    /// the batch got no response, a server error, or no entry for the
+    /// message. Sending it again may email it twice, unless it goes through
+    /// an `IdempotentClient` (`idempotency` feature) with a key.
+    pub fn is_uncertain(&self) -> bool {
+        match self {
+            BatchError::Query { source } => match &**source {
+                QueryError::Client { .. } => true,
+                QueryError::Api(error) => error.status.is_server_error(),
+                _ => false,
+            },
+            BatchError::MissingResponse => true,
+            BatchError::TooLarge { .. } => false,
+        }
+    }
+}
+
+/// A message Postmark accepted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sent {
+    pub to: Option<String>,
+    pub message_id: Option<String>,
+    pub submitted_at: Option<String>,
+}
+
+/// Why a message of a [`BatchOutcome`] was not sent.
+#[derive(Error, Debug)]
+pub enum BatchFailure<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Postmark refused the message, and accepted the rest of its batch.
+    #[error("message rejected: error_code={error_code}, message={message:?}")]
+    Rejected {
+        error_code: PostmarkErrorCode,
+        message: String,
+    },
+    /// The message did not get a response of its own.
+    #[error("{}", source)]
+    NotSent { source: BatchError<E> },
+}
+
+impl<E> BatchFailure<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Whether the message was not sent, and sending it again may succeed:
+    /// it was rate limited, or [`BatchError::is_retryable`].
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            BatchFailure::Rejected { error_code, .. } => error_code.is_rate_limited(),
+            BatchFailure::NotSent { source } => source.is_retryable(),
+        }
+    }
+
+    /// Whether the message may have been sent anyway, see
+    /// [`BatchError::is_uncertain`].
+    pub fn is_uncertain(&self) -> bool {
+        match self {
+            BatchFailure::Rejected { .. } => false,
+            BatchFailure::NotSent { source } => source.is_uncertain(),
+        }
+    }
+}
+
+/// The message of `response` if Postmark accepted it.
+fn accepted<E>(response: SendEmailResponse) -> Result<Sent, BatchFailure<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    if !response.error_code.is_ok() {
+        return Err(BatchFailure::Rejected {
+            error_code: response.error_code,
+            message: response.message,
+        });
+    }
+    Ok(Sent {
+        to: response.to,
+        message_id: response.message_id,
+        submitted_at: response.submitted_at,
+    })
+}
+
+/// One input of a [`BatchOutcome`] and what became of it.
+#[derive(Debug)]
+pub struct BatchEntry<K, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// The message, or the key it was given with.
+    pub input: K,
+    pub result: Result<Sent, BatchFailure<E>>,
+}
+
+/// The result of every message sent by [`BatchSender::execute`], in the
+/// order they were given.
+#[derive(Debug)]
+pub struct BatchOutcome<K, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    entries: Vec<BatchEntry<K, E>>,
+}
+
+impl<K, E> BatchOutcome<K, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// Every entry, in input order.
+    pub fn entries(&self) -> &[BatchEntry<K, E>] {
+        &self.entries
+    }
+
+    pub fn into_entries(self) -> Vec<BatchEntry<K, E>> {
+        self.entries
+    }
+
+    /// Whether every message was sent.
+    pub fn is_success(&self) -> bool {
+        self.entries.iter().all(|entry| entry.result.is_ok())
+    }
+
+    /// The messages Postmark accepted.
+    pub fn sent(&self) -> impl Iterator<Item = (&K, &Sent)> {
+        self.entries
+            .iter()
+            .filter_map(|entry| Some((&entry.input, entry.result.as_ref().ok()?)))
+    }
+
+    /// The messages that were not sent.
+    pub fn failed(&self) -> impl Iterator<Item = &BatchEntry<K, E>> {
+        self.entries.iter().filter(|entry| entry.result.is_err())
+    }
+
+    /// The messages that were not sent but may be if sent again, see
+    /// [`BatchFailure::is_retryable`].
+    pub fn retryable(&self) -> impl Iterator<Item = &BatchEntry<K, E>> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.result.as_ref().is_err_and(BatchFailure::is_retryable))
+    }
+
+    /// The messages whose batch failed in a way that may have sent them
+    /// anyway, see [`BatchFailure::is_uncertain`].
+    pub fn uncertain(&self) -> impl Iterator<Item = &BatchEntry<K, E>> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.result.as_ref().is_err_and(BatchFailure::is_uncertain))
+    }
+
+    /// The inputs of [`BatchOutcome::retryable`], to send them again.
+    pub fn into_retryable(self) -> Vec<K> {
+        self.entries
+            .into_iter()
+            .filter(|entry| entry.result.as_ref().is_err_and(BatchFailure::is_retryable))
+            .map(|entry| entry.input)
+            .collect()
+    }
+}
+
+/// A message sent through a batch endpoint.
+pub trait BatchMessage: Serialize + Sized {
+    /// The request sending a batch of these messages.
+    type Batch: Endpoint<Response = SendEmailBatchResponse>;
+
+    fn into_batch(messages: Vec<Self>) -> Self::Batch;
+
+    /// The messages of `batch`, in order.
+    fn from_batch(batch: Self::Batch) -> Vec<Self>;
+}
+
+impl BatchMessage for SendEmailRequest {
+    type Batch = Vec<SendEmailRequest>;
+
+    fn into_batch(messages: Vec<Self>) -> Self::Batch {
+        messages
+    }
+
+    fn from_batch(batch: Self::Batch) -> Vec<Self> {
+        batch
+    }
+}
+
+impl BatchMessage for SendEmailWithTemplateRequest {
+    type Batch = SendEmailBatchWithTemplatesRequest;
+
+    fn into_batch(messages: Vec<Self>) -> Self::Batch {
+        SendEmailBatchWithTemplatesRequest { messages }
+    }
+
+    fn from_batch(batch: Self::Batch) -> Vec<Self> {
+        batch.messages
+    }
+}
+
+/// Sends messages in as many batches as needed, see the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct BatchSender<C> {
+    client: C,
+    concurrency: usize,
+    max_messages: usize,
+    max_bytes: usize,
+}
+
+impl<C> BatchSender<C> {
+    /// Send up to [`DEFAULT_CONCURRENCY`] batches at a time, within
+    /// Postmark's limits.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            concurrency: DEFAULT_CONCURRENCY,
+            max_messages: MAX_BATCH_MESSAGES,
+            max_bytes: MAX_BATCH_BYTES,
+        }
+    }
+
+    /// Send up to `concurrency` batches at a time, at least one.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Put at most `max_messages` messages in a batch, at least one and
+    /// at most [`MAX_BATCH_MESSAGES`].
+    pub fn with_max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = max_messages.clamp(1, MAX_BATCH_MESSAGES);
+        self
+    }
+
+    /// Keep batches under `max_bytes` bytes of JSON, at most
+    /// [`MAX_BATCH_BYTES`].
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes.min(MAX_BATCH_BYTES);
+        self
+    }
+
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+}
+
+impl<C> BatchSender<C>
+where
+    C: Client + Send + Sync,
+{
+    /// Send `messages`, and return their results in the same order.
+    ///
+    /// Messages Postmark refused one by one are `Ok`, with the error code
+    /// of their [`SendEmailResponse`].
+    pub async fn send<M>(
+        &self,
+        messages: Vec<M>,
+    ) -> Vec<Result<SendEmailResponse, BatchError<C::Error>>>
+    where
+        M: BatchMessage + Send,
+        M::Batch: Send + Sync,
+    {
+        self.send_back(messages)
+            .await
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect()
+    }
+
+    /// Send `messages`, and pair each of them with its result.
+    pub async fn execute<M>(&self, messages: Vec<M>) -> BatchOutcome<M, C::Error>
+    where
+        M: BatchMessage + Send,
+        M::Batch: Send + Sync,
+    {
+        outcome(self.send_back(messages).await)
+    }
+
+    /// Like [`BatchSender::send`], but gives each message back with its
+    /// result, taking it out of its batch once sent.
+    async fn send_back<M>(&self, messages: Vec<M>) -> Vec<(M, SendResult<C::Error>)>
+    where
+        M: BatchMessage + Send,
+        M::Batch: Send + Sync,
+    {
+        let mut results: Vec<_> = messages.iter().map(|_| None).collect();
+        let overhead =
+            serde_json::to_vec(M::into_batch(Vec::new()).body()).map_or(0, |json| json.len());
+
+        // Indices and messages of each batch.
+        let mut batches: Vec<(Vec<usize>, Vec<M>)> = Vec::new();
+        let mut batch_size = overhead;
+        for (index, message) in messages.into_iter().enumerate() {
+            // Each message but the first is preceded by a comma.
+            let size = match serde_json::to_vec(&message) {
+                Ok(json) => json.len() + 1,
+                Err(source) => {
+                    let source = Arc::new(QueryError::Json { source });
+                    results[index] = Some((message, Err(BatchError::Query { source })));
+                    continue;
+                }
+            };
+            if overhead + size > self.max_bytes {
+                let error = BatchError::TooLarge {
+                    size: overhead + size,
+                    max: self.max_bytes,
+                };
+                results[index] = Some((message, Err(error)));
+                continue;
+            }
+
+            let full = batches.last().is_none_or(|(indices, _)| {
+                indices.len() >= self.max_messages || batch_size + size > self.max_bytes
+            });
+            if full {
+                batches.push((Vec::new(), Vec::new()));
+                batch_size = overhead;
+            }
+            let (indices, batch) = batches.last_mut().expect("a batch was just pushed");
+            indices.push(index);
+            batch.push(message);
+            batch_size += size;
+        }
+
+        let sent = bounded(
+            self.concurrency,
+            batches.into_iter().map(|(indices, batch)| async move {
+                let batch = M::into_batch(batch);
+                let result = match client::send(&batch, &self.client).await {
+                    Ok(response) => client::parse_response(&batch, response),
+                    Err(error) => Err(error),
+                };
+                (indices, M::from_batch(batch), result)
+            }),
+        )
+        .await;
+
+        for (indices, messages, result) in sent {
+            let messages = indices.into_iter().zip(messages);
+            match result {
+                Ok(responses) => {
+                    let mut responses = responses.into_iter();
+                    for (index, message) in messages {
+                        let response = responses.next().ok_or(BatchError::MissingResponse);
+                        results[index] = Some((message, response));
+                    }
+                }
+                Err(error) => {
+                    let source = Arc::new(error);
+                    for (index, message) in messages {
+                        let error = BatchError::Query {
+                            source: source.clone(),
+                        };
+                        results[index] = Some((message, Err(error)));
+                    }
+                }
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.expect("every message has a result"))
+            .collect()
+    }
+
+    /// Send the messages of `messages`, and pair the key given with each of
+    /// them with its result.
+    pub async fn execute_keyed<K, M>(
+        &self,
+        messages: impl IntoIterator<Item = (K, M)>,
+    ) -> BatchOutcome<K, C::Error>
+    where
+        M: BatchMessage + Send,
+        M::Batch: Send + Sync,
+    {
+        let (keys, messages): (Vec<K>, Vec<M>) = messages.into_iter().unzip();
+        let results = self.send(messages).await;
+
+        outcome(keys.into_iter().zip(results))
+    }
+}
+
+type SendResult<E> = Result<SendEmailResponse, BatchError<E>>;
+
+/// Pair each input with the outcome of its result.
+fn outcome<K, E>(results: impl IntoIterator<Item = (K, SendResult<E>)>) -> BatchOutcome<K, E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    BatchOutcome {
+        entries: results
+            .into_iter()
+            .map(|(input, result)| BatchEntry {
+                input,
+                result: result
+                    .map_err(|source| BatchFailure::NotSent { source })
+                    .and_then(accepted),
+            })
+            .collect(),
+    }
+}
+
+/// Run `futures`, at most `limit` at a time, and return their outputs in
+/// order.
+async fn bounded<F>(limit: usize, futures: impl IntoIterator<Item = F>) -> Vec<F::Output>
+where
+    F: Future,
+{
+    let mut waiting = futures.into_iter().enumerate();
+    let mut running: Vec<(usize, Pin<Box<F>>)> = Vec::new();
+    let mut outputs = Vec::new();
+
+    poll_fn(|cx| {
+        loop {
+            while running.len() < limit {
+                let Some((index, future)) = waiting.next() else {
+                    break;
+                };
+                running.push((index, Box::pin(future)));
+            }
+            if running.is_empty() {
+                return Poll::Ready(());
+            }
+
+            let before = running.len();
+            running.retain_mut(|(index, future)| match future.as_mut().poll(cx) {
+                Poll::Ready(output) => {
+                    outputs.push((*index, output));
+                    false
+                }
+                Poll::Pending => true,
+            });
+            if running.len() == before {
+                return Poll::Pending;
+            }
+        }
+    })
+    .await;
+
+    outputs.sort_by_key(|(index, _)| *index);
+    outputs.into_iter().map(|(_, output)| output).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use http::{Method, Request, Response, StatusCode};
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::api::Body;
+    use crate::testing::{MockClient, MockResponse};
+
+    fn email(to: &str) -> SendEmailRequest {
+        SendEmailRequest::builder()
+            .from("me@example.com")
+            .to(to)
+            .body(Body::text("hello".into()))
+            .build()
+    }
+
+    /// Accepts every message but those to `fail@example.com` and
+    /// `limited@example.com`, which fail their whole batch with a `500` and
+    /// a `429`, and those to `inactive@example.com`, which are rejected.
+    /// Tracks how many batches are in flight.
+    #[derive(Default)]
+    struct EchoClient {
+        in_flight: Mutex<(usize, usize)>,
+        batches: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl Client for EchoClient {
+        type Error = std::convert::Infallible;
+
+        async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+            {
+                let mut in_flight = self.in_flight.lock().unwrap();
+                in_flight.0 += 1;
+                in_flight.1 = in_flight.1.max(in_flight.0);
+            }
+            for _ in 0..3 {
+                tokio::task::yield_now().await;
+            }
+            self.in_flight.lock().unwrap().0 -= 1;
+
+            let messages: Vec<Value> = serde_json::from_slice(req.body()).expect("batch");
+            self.batches.lock().unwrap().push(messages.len());
+            let (status, body) = if messages.iter().any(|m| m["To"] == "fail@example.com") {
+                (StatusCode::INTERNAL_SERVER_ERROR, json!({}))
+            } else if messages.iter().any(|m| m["To"] == "limited@example.com") {
+                (StatusCode::TOO_MANY_REQUESTS, json!({}))
+            } else {
+                let responses: Vec<_> = messages
+                    .iter()
+                    .map(|m| match m["To"].as_str() {
+                        Some("inactive@example.com") => json!({
+                            "To": m["To"],
+                            "ErrorCode": 406,
+                            "Message": "Inactive recipient",
+                        }),
+                        _ => json!({
+                            "To": m["To"],
+                            "ErrorCode": 0,
+                            "Message": "OK",
+                            "MessageID": "b7bc2f4a",
+                            "SubmittedAt": "2024-01-01T00:00:00Z",
+                        }),
+                    })
+                    .collect();
+                (StatusCode::OK, json!(responses))
+            };
+            Ok(Response::builder()
+                .status(status)
+                .body(Bytes::from(body.to_string()))
+                .expect("response"))
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_batches_concurrently_and_keeps_the_input_order() {
+        let sender = BatchSender::new(EchoClient::default())
+            .with_max_messages(3)
+            .with_concurrency(2);
+        let emails: Vec<_> = (0..10)
+            .map(|i| email(&format!("{i}@example.com")))
+            .collect();
+
+        let results = sender.send(emails).await;
+
+        let to: Vec<_> = results
+            .into_iter()
+            .map(|result| result.expect("sent").to.expect("to"))
+            .collect();
+        let expected: Vec<_> = (0..10).map(|i| format!("{i}@example.com")).collect();
+        assert_eq!(to, expected);
+        assert_eq!(*sender.client().batches.lock().unwrap(), [3, 3, 3, 1]);
+        assert_eq!(sender.client().in_flight.lock().unwrap().1, 2);
+    }
+
+    #[tokio::test]
+    async fn splits_by_size_and_skips_messages_too_large_for_any_batch() {
+        let size = serde_json::to_vec(&email("0@example.com")).unwrap().len();
+        let sender = BatchSender::new(EchoClient::default()).with_max_bytes(2 * size + 10);
+        let mut emails: Vec<_> = (0..5).map(|i| email(&format!("{i}@example.com"))).collect();
+        emails[2].body = Body::text("x".repeat(3 * size));
+
+        let results = sender.send(emails).await;
+
+        assert!(matches!(results[2], Err(BatchError::TooLarge { .. })));
+        assert!(results.iter().filter(|result| result.is_ok()).count() == 4);
+        assert_eq!(*sender.client().batches.lock().unwrap(), [2, 2]);
+    }
+
+    #[tokio::test]
+    async fn a_failed_batch_fails_each_of_its_messages() {
+        let sender = BatchSender::new(EchoClient::default()).with_max_messages(2);
+        let emails = vec![
+            email("a@example.com"),
+            email("b@example.com"),
+            email("fail@example.com"),
+            email("c@example.com"),
+        ];
+
+        let results = sender.send(emails).await;
+
+        assert!(results[0].is_ok() && results[1].is_ok());
+        let (Err(BatchError::Query { source: first }), Err(BatchError::Query { source: second })) =
+            (&results[2], &results[3])
+        else {
+            panic!("batch error expected: {results:?}");
+        };
+        assert!(Arc::ptr_eq(first, second));
+    }
+
+    #[tokio::test]
+    async fn sends_template_batches() {
+        let client = MockClient::new();
+        client.respond(
+            Method::POST,
+            "/email/batchWithTemplates",
+            MockResponse::ok(json!([{ "To": "a@example.com", "ErrorCode": 0, "Message": "OK" }])),
+        );
+        let sender = BatchSender::new(client.clone());
+
+        let message = SendEmailWithTemplateRequest::builder()
+            .from("me@example.com")
+            .to("a@example.com")
+            .template_alias("welcome".to_string())
+            .build();
+        let results = sender.send(vec![message]).await;
+
+        assert_eq!(results.len(), 1);
+        let sent: Vec<Value> = client.sent(Method::POST, "/email/batchWithTemplates");
+        assert_eq!(sent[0]["Messages"][0]["TemplateAlias"], "welcome");
+    }
+
+    #[tokio::test]
+    async fn outcome_pairs_inputs_with_their_results() {
+        let sender = BatchSender::new(EchoClient::default()).with_max_messages(2);
+        let emails = vec![
+            email("a@example.com"),
+            email("inactive@example.com"),
+            email("fail@example.com"),
+            email("b@example.com"),
+            email("limited@example.com"),
+        ];
+
+        let outcome = sender.execute(emails).await;
+
+        assert!(!outcome.is_success());
+        let sent: Vec<_> = outcome.sent().map(|(email, _)| email.to.as_str()).collect();
+        assert_eq!(sent, ["a@example.com"]);
+        let (_, first) = outcome.sent().next().expect("sent");
+        assert_eq!(first.message_id.as_deref(), Some("b7bc2f4a"));
+        assert_eq!(first.submitted_at.as_deref(), Some("2024-01-01T00:00:00Z"));
+
+        let failed: Vec<_> = outcome
+            .failed()
+            .map(|entry| entry.input.to.as_str())
+            .collect();
+        assert_eq!(
+            failed,
+            [
+                "inactive@example.com",
+                "fail@example.com",
+                "b@example.com",
+                "limited@example.com"
+            ]
+        );
+        assert!(matches!(
+            outcome.entries()[1].result,
+            Err(BatchFailure::Rejected {
+                error_code: PostmarkErrorCode::InactiveRecipient,
+                ..
+            })
+        ));
+
+        let uncertain: Vec<_> = outcome
+            .uncertain()
+            .map(|entry| entry.input.to.as_str())
+            .collect();
+        assert_eq!(uncertain, ["fail@example.com", "b@example.com"]);
+
+        let retry: Vec<_> = outcome
+            .into_retryable()
+            .into_iter()
+            .map(|email| email.to)
+            .collect();
+        assert_eq!(retry, ["limited@example.com"]);
+    }
+
+    #[tokio::test]
+    async fn outcome_keeps_caller_keys() {
+        let sender = BatchSender::new(EchoClient::default());
+
+        let outcome = sender
+            .execute_keyed([
+                (1, email("a@example.com")),
+                (2, email("inactive@example.com")),
+            ])
+            .await;
+
+        let keys: Vec<_> = outcome.failed().map(|entry| entry.input).collect();
+        assert_eq!(keys, [2]);
+        assert_eq!(outcome.retryable().count(), 0);
+    }
+}
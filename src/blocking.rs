@@ -0,0 +1,340 @@
+//! Synchronous counterparts of [`Client`](crate::Client) and
+//! [`Query`](crate::Query), for programs that do not run an async runtime.
+//!
+//! Every [`Endpoint`] can be executed with [`BlockingQuery::execute_blocking`].
+//! A `reqwest` blocking based [`PostmarkClient`] is provided, and you can plug
+//! your own HTTP stack by implementing the blocking [`Client`] trait.
+//!
+//! ```no_run
+//! use postmark::api::{Body, email::SendEmailRequest};
+//! use postmark::blocking::{BlockingQuery, PostmarkClient};
+//!
+//! let client = PostmarkClient::builder()
+//!   .server_token("<sometoken>")
+//!   .build();
+//!
+//! let req = SendEmailRequest::builder()
+//!   .from("me@example.com")
+//!   .to("you@example.com")
+//!   .body(Body::text("Hi, this is me!".to_string()))
+//!   .build();
+//! let resp = req.execute_blocking(&client);
+//! ```
+
+use std::convert::TryInto;
+use std::error::Error;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{Request, Response};
+use typed_builder::TypedBuilder;
+
+use crate::client::{build_request, parse_response};
+use crate::reqwest::{PostmarkClientError, USER_AGENT};
+use crate::transport::prepare_request;
+use crate::{Endpoint, POSTMARK_API_URL, QueryError, RequestTimeout, ScopedClient};
+
+/// A trait representing a client which can synchronously communicate with a
+/// Postmark instance.
+pub trait Client {
+    /// The errors which may occur for this client.
+    type Error: Error + Send + Sync + 'static;
+    /// Execute the request which was formed by [`Endpoint`]
+    fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error>;
+}
+
+/// A trait which represents a synchronous query which may be made to a
+/// Postmark client.
+pub trait BlockingQuery<C> {
+    /// The Result of executing a query
+    type Result;
+    /// Perform the query against the client, blocking the current thread.
+    fn execute_blocking(self, client: &C) -> Self::Result;
+}
+
+/// Extension method for all endpoints to execute themselves against a
+/// blocking client.
+impl<T, C> BlockingQuery<C> for T
+where
+    T: Endpoint,
+    C: Client,
+{
+    /// Returns the endpoint response or the client error.
+    type Result = Result<T::Response, QueryError<C::Error>>;
+
+    fn execute_blocking(self, client: &C) -> Self::Result {
+        let http_req = build_request(&self)?;
+        let response = client.execute(http_req).map_err(QueryError::client)?;
+        parse_response(&self, response)
+    }
+}
+
+/// A representation of the synchronous Postmark API for a single user.
+/// Separate users should use separate instances of this.
+///
+/// A reqwest blocking based [`Client`], configured like
+/// [`reqwest::PostmarkClient`](crate::reqwest::PostmarkClient).
+///
+/// ```
+/// # use postmark::blocking::PostmarkClient;
+/// let client = PostmarkClient::builder()
+///   .base_url("https://api.postmarkapp.com")
+///   .server_token("<sometoken>")
+///   .build();
+/// ```
+#[derive(TypedBuilder, Clone)]
+pub struct PostmarkClient {
+    #[builder(default, setter(into, strip_option))]
+    pub server_token: Option<String>,
+    #[builder(default, setter(into, strip_option))]
+    pub account_token: Option<String>,
+    #[builder(default=POSTMARK_API_URL.into(), setter(into))]
+    pub base_url: String,
+    /// Time allowed for each request, from connecting to reading the whole
+    /// response. A [`RequestTimeout`] on a request takes precedence.
+    #[builder(default, setter(strip_option))]
+    pub timeout: Option<Duration>,
+    /// The `reqwest` client requests are sent with. Defaults to one built
+    /// from [`PostmarkClient::http_client_builder`].
+    #[builder(default = default_http_client())]
+    client: ::reqwest::blocking::Client,
+}
+
+fn default_http_client() -> ::reqwest::blocking::Client {
+    PostmarkClient::http_client_builder()
+        .build()
+        .expect("default reqwest client builds")
+}
+
+impl std::fmt::Debug for PostmarkClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            server_token: ref _server_token,
+            account_token: ref _account_token,
+            base_url: ref _base_url,
+            timeout: ref _timeout,
+            client: ref _client,
+        } = *self;
+
+        let mut builder = f.debug_struct("PostmarkClient");
+        builder.field("server_token", &_server_token.as_ref().map(|_| "***"));
+        builder.field("account_token", &_account_token.as_ref().map(|_| "***"));
+        builder.field("base_url", _base_url);
+        builder.field("timeout", _timeout);
+        builder.finish()
+    }
+}
+
+impl Default for PostmarkClient {
+    fn default() -> Self {
+        Self {
+            base_url: POSTMARK_API_URL.into(),
+            server_token: None,
+            account_token: None,
+            timeout: None,
+            client: default_http_client(),
+        }
+    }
+}
+
+impl Client for PostmarkClient {
+    type Error = PostmarkClientError;
+
+    fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let req = prepare_request::<Self::Error>(
+            req,
+            self.server_token.as_deref(),
+            self.account_token.as_deref(),
+            &self.base_url,
+        )?;
+
+        let timeout = match req.extensions().get::<RequestTimeout>() {
+            Some(RequestTimeout(timeout)) => Some(*timeout),
+            None => self.timeout,
+        };
+
+        let mut reqwest_req: ::reqwest::blocking::Request = req.try_into()?;
+        if timeout.is_some() {
+            *reqwest_req.timeout_mut() = timeout;
+        }
+        let reqwest_rsp = self.client.execute(reqwest_req)?;
+
+        let mut rsp = Response::builder()
+            .status(reqwest_rsp.status())
+            .version(reqwest_rsp.version());
+
+        let headers = rsp.headers_mut().unwrap();
+        for (k, v) in reqwest_rsp.headers() {
+            headers.insert(k, v.clone());
+        }
+
+        Ok(rsp.body(reqwest_rsp.bytes()?)?)
+    }
+}
+
+impl PostmarkClient {
+    /// A [`reqwest::blocking::ClientBuilder`] set up the way the default
+    /// client is, see
+    /// [`reqwest::PostmarkClient::http_client_builder`](crate::reqwest::PostmarkClient::http_client_builder).
+    pub fn http_client_builder() -> ::reqwest::blocking::ClientBuilder {
+        ::reqwest::blocking::Client::builder().user_agent(USER_AGENT)
+    }
+
+    pub fn execute_endpoint<T>(
+        &self,
+        request: T,
+    ) -> Result<T::Response, QueryError<PostmarkClientError>>
+    where
+        T: Endpoint,
+    {
+        request.execute_blocking(self)
+    }
+
+    /// Execute `request`, failing with a timeout error when it takes longer
+    /// than `timeout`, whatever [`PostmarkClient::timeout`] is.
+    pub fn execute_endpoint_with_timeout<T>(
+        &self,
+        request: T,
+        timeout: Duration,
+    ) -> Result<T::Response, QueryError<PostmarkClientError>>
+    where
+        T: Endpoint,
+    {
+        request.execute_blocking(&WithTimeout {
+            client: self,
+            timeout,
+        })
+    }
+
+    /// Borrow this client to send requests with another server token,
+    /// sharing its connection pool.
+    pub fn with_server_token(&self, server_token: impl Into<String>) -> ScopedClient<'_, Self> {
+        ScopedClient::new(self, server_token)
+    }
+}
+
+/// Sends every request with a [`RequestTimeout`], for
+/// [`PostmarkClient::execute_endpoint_with_timeout`].
+struct WithTimeout<'a> {
+    client: &'a PostmarkClient,
+    timeout: Duration,
+}
+
+impl Client for WithTimeout<'_> {
+    type Error = PostmarkClientError;
+
+    fn execute(&self, mut req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        req.extensions_mut().insert(RequestTimeout(self.timeout));
+        self.client.execute(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httptest::matchers::{all_of, contains, request};
+    use httptest::{Expectation, Server, responders::*};
+    use serde_json::json;
+
+    use super::*;
+    use crate::PostmarkErrorCode;
+    use crate::api::Body;
+    use crate::api::email::SendEmailRequest;
+
+    #[test]
+    fn send_email_blocking() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/email"),
+                request::headers(contains(("x-postmark-server-token", "server-token"))),
+            ])
+            .respond_with(json_encoded(json!({
+                "To": "receiver@example.com",
+                "SubmittedAt": "2014-02-17T07:25:01.4178645-05:00",
+                "MessageID": "0a129aee-e1cd-480d-b08d-4f48548ff48d",
+                "ErrorCode": 0,
+                "Message": "OK"
+            }))),
+        );
+
+        let client = PostmarkClient::builder()
+            .base_url(server.url("/").to_string())
+            .server_token("server-token")
+            .build();
+
+        let req = SendEmailRequest::builder()
+            .from("pa@example.com")
+            .to("mathieu@example.com")
+            .body(Body::text("hello matt".into()))
+            .build();
+
+        let resp = client.execute_endpoint(req).expect("json decode");
+        assert_eq!(
+            resp.message_id.as_deref(),
+            Some("0a129aee-e1cd-480d-b08d-4f48548ff48d")
+        );
+    }
+
+    #[test]
+    fn non_success_status_returns_api_error() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/email")).respond_with(
+                status_code(422).body(r#"{"ErrorCode":300,"Message":"Invalid 'From' address"}"#),
+            ),
+        );
+
+        let client = PostmarkClient::builder()
+            .base_url(server.url("/").to_string())
+            .build();
+
+        let req = SendEmailRequest::builder()
+            .from("invalid")
+            .to("mathieu@example.com")
+            .body(Body::text("hello matt".into()))
+            .build();
+
+        let error = req.execute_blocking(&client).expect_err("api error");
+        assert!(matches!(
+            error,
+            QueryError::Api(error)
+                if error.error_code == Some(PostmarkErrorCode::InvalidEmailRequest)
+        ));
+    }
+
+    #[test]
+    fn with_server_token_overrides_the_client_token() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/email"),
+                request::headers(contains(("x-postmark-server-token", "tenant-token"))),
+            ])
+            .times(2)
+            .respond_with(json_encoded(json!({
+                "MessageID": "0a129aee-e1cd-480d-b08d-4f48548ff48d",
+                "ErrorCode": 0,
+                "Message": "OK"
+            }))),
+        );
+
+        let client = PostmarkClient::builder()
+            .base_url(server.url("/").to_string())
+            .server_token("server-token")
+            .build();
+        let tenant = client.with_server_token("tenant-token");
+
+        for _ in 0..2 {
+            SendEmailRequest::builder()
+                .from("pa@example.com")
+                .to("mathieu@example.com")
+                .body(Body::text("hello matt".into()))
+                .build()
+                .execute_blocking(&tenant)
+                .expect("json decode");
+        }
+    }
+}
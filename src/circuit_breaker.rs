@@ -0,0 +1,341 @@
+//! A [`Client`] decorator failing fast while Postmark is unavailable.
+//!
+//! [`CircuitBreakerClient`] counts transport errors and `5xx` responses. Once
+//! [`CircuitBreakerPolicy::failure_threshold`] of them happen in a row within
+//! [`CircuitBreakerPolicy::window`], the circuit opens and requests fail
+//! immediately with [`QueryError::CircuitOpen`](crate::QueryError::CircuitOpen)
+//! instead of waiting on timeouts. After
+//! [`CircuitBreakerPolicy::open_duration`] a single probe request is let
+//! through: its success closes the circuit, its failure opens it again.
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use postmark::circuit_breaker::{CircuitBreakerClient, CircuitState};
+//!
+//! let client = CircuitBreakerClient::new(PostmarkClient::default());
+//!
+//! if client.state() == CircuitState::Open {
+//!     // Queue the email for later instead of sending it now.
+//! }
+//! ```
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Request, Response};
+use thiserror::Error;
+use typed_builder::TypedBuilder;
+
+use crate::Client;
+
+/// When a [`CircuitBreakerClient`] opens and for how long.
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct CircuitBreakerPolicy {
+    /// Failures in a row that open the circuit.
+    #[builder(default = 5)]
+    pub failure_threshold: u32,
+    /// Failures further apart than this are not counted together.
+    #[builder(default = Duration::from_secs(30))]
+    pub window: Duration,
+    /// How long the circuit stays open before a probe request is let through.
+    #[builder(default = Duration::from_secs(30))]
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+/// The state of a [`CircuitBreakerClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent.
+    Closed,
+    /// Requests fail without being sent.
+    Open,
+    /// One probe request is in flight, the others fail without being sent.
+    HalfOpen,
+}
+
+/// Returned, inside [`CircuitBreakerError::Open`], for requests rejected by
+/// an open circuit.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("circuit breaker is open")]
+pub struct CircuitOpen;
+
+#[derive(Error, Debug)]
+pub enum CircuitBreakerError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// The request was not sent because the circuit is open.
+    #[error("{}", source)]
+    Open { source: CircuitOpen },
+    /// The wrapped client failed.
+    #[error("{}", source)]
+    Inner { source: E },
+}
+
+#[derive(Debug)]
+struct Breaker {
+    state: CircuitState,
+    failures: u32,
+    first_failure_at: Option<Instant>,
+    opened_at: Option<Instant>,
+}
+
+/// Wraps a [`Client`] and stops sending requests while they keep failing,
+/// see the [module documentation](self).
+#[derive(Debug)]
+pub struct CircuitBreakerClient<C> {
+    inner: C,
+    policy: CircuitBreakerPolicy,
+    breaker: Mutex<Breaker>,
+}
+
+impl<C> CircuitBreakerClient<C> {
+    /// Wrap `inner` with the default [`CircuitBreakerPolicy`].
+    pub fn new(inner: C) -> Self {
+        Self::with_policy(inner, CircuitBreakerPolicy::default())
+    }
+
+    pub fn with_policy(inner: C, policy: CircuitBreakerPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            breaker: Mutex::new(Breaker {
+                state: CircuitState::Closed,
+                failures: 0,
+                first_failure_at: None,
+                opened_at: None,
+            }),
+        }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn policy(&self) -> &CircuitBreakerPolicy {
+        &self.policy
+    }
+
+    /// The current state. An open circuit whose
+    /// [`CircuitBreakerPolicy::open_duration`] elapsed is reported as
+    /// [`CircuitState::HalfOpen`], as the next request will probe.
+    pub fn state(&self) -> CircuitState {
+        let breaker = self.lock();
+        match breaker.state {
+            CircuitState::Open if self.open_elapsed(&breaker, Instant::now()) => {
+                CircuitState::HalfOpen
+            }
+            state => state,
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Breaker> {
+        self.breaker.lock().expect("circuit breaker state poisoned")
+    }
+
+    fn open_elapsed(&self, breaker: &Breaker, now: Instant) -> bool {
+        breaker
+            .opened_at
+            .is_some_and(|opened_at| now.duration_since(opened_at) >= self.policy.open_duration)
+    }
+
+    /// Whether a request may be sent now.
+    fn admit(&self) -> bool {
+        let mut breaker = self.lock();
+        match breaker.state {
+            CircuitState::Closed => true,
+            // A half open circuit lets one probe through per open duration,
+            // so a probe that never completes does not keep it stuck.
+            CircuitState::Open | CircuitState::HalfOpen
+                if self.open_elapsed(&breaker, Instant::now()) =>
+            {
+                breaker.state = CircuitState::HalfOpen;
+                breaker.opened_at = Some(Instant::now());
+                true
+            }
+            CircuitState::Open | CircuitState::HalfOpen => false,
+        }
+    }
+
+    fn record(&self, failed: bool) {
+        let mut breaker = self.lock();
+        let now = Instant::now();
+
+        if !failed {
+            breaker.state = CircuitState::Closed;
+            breaker.failures = 0;
+            breaker.first_failure_at = None;
+            breaker.opened_at = None;
+            return;
+        }
+
+        let in_window = breaker
+            .first_failure_at
+            .is_some_and(|first| now.duration_since(first) <= self.policy.window);
+        if !in_window {
+            breaker.failures = 0;
+            breaker.first_failure_at = Some(now);
+        }
+        breaker.failures += 1;
+
+        if breaker.state == CircuitState::HalfOpen
+            || breaker.failures >= self.policy.failure_threshold
+        {
+            breaker.state = CircuitState::Open;
+            breaker.opened_at = Some(now);
+        }
+    }
+}
+
+#[async_trait]
+impl<C> Client for CircuitBreakerClient<C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = CircuitBreakerError<C::Error>;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        if !self.admit() {
+            return Err(CircuitBreakerError::Open {
+                source: CircuitOpen,
+            });
+        }
+
+        let result = self.inner.execute(req).await;
+        self.record(match &result {
+            Ok(rsp) => rsp.status().is_server_error(),
+            Err(_) => true,
+        });
+
+        result.map_err(|source| CircuitBreakerError::Inner { source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+
+    use super::*;
+    use crate::api::templates::{ListTemplatesRequest, ListTemplatesResponse};
+    use crate::testing::{MockClient, MockResponse};
+    use crate::{Query, QueryError};
+
+    fn policy(open_duration: Duration) -> CircuitBreakerPolicy {
+        CircuitBreakerPolicy::builder()
+            .failure_threshold(3)
+            .open_duration(open_duration)
+            .build()
+    }
+
+    fn templates() -> MockResponse {
+        MockResponse::ok(ListTemplatesResponse {
+            total_count: 0,
+            templates: vec![],
+        })
+    }
+
+    async fn list<C>(client: &C) -> Result<ListTemplatesResponse, QueryError<C::Error>>
+    where
+        C: Client + Send + Sync,
+    {
+        ListTemplatesRequest::builder()
+            .build()
+            .execute(client)
+            .await
+    }
+
+    #[tokio::test]
+    async fn opens_after_consecutive_server_errors_and_fails_fast() {
+        let mock = MockClient::new();
+        mock.respond(Method::GET, "/templates", MockResponse::server_error());
+        let client =
+            CircuitBreakerClient::with_policy(mock.clone(), policy(Duration::from_secs(60)));
+
+        for _ in 0..3 {
+            let error = list(&client).await.expect_err("server error");
+            assert!(matches!(error, QueryError::Api(_)));
+        }
+        assert_eq!(client.state(), CircuitState::Open);
+
+        let error = list(&client).await.expect_err("open circuit");
+        assert!(matches!(error, QueryError::CircuitOpen));
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn client_errors_count_and_successes_reset() {
+        let mock = MockClient::new();
+        let client =
+            CircuitBreakerClient::with_policy(mock.clone(), policy(Duration::from_secs(60)));
+
+        // No response queued: the mock fails like a transport error would.
+        for _ in 0..2 {
+            let error = list(&client).await.expect_err("client error");
+            assert!(matches!(error, QueryError::Client { .. }));
+        }
+
+        mock.respond(Method::GET, "/templates", templates());
+        list(&client).await.expect("templates");
+        assert_eq!(client.state(), CircuitState::Closed);
+
+        let mock = MockClient::new();
+        mock.respond(
+            Method::GET,
+            "/templates",
+            MockResponse::unprocessable(1101, "Template not found"),
+        );
+        let client = CircuitBreakerClient::with_policy(mock, policy(Duration::from_secs(60)));
+        for _ in 0..5 {
+            let _ = list(&client).await;
+        }
+        assert_eq!(client.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn half_open_probe_closes_or_reopens_the_circuit() {
+        let mock = MockClient::new();
+        mock.respond(Method::GET, "/templates", MockResponse::server_error());
+        let client = CircuitBreakerClient::with_policy(mock.clone(), policy(Duration::ZERO));
+
+        for _ in 0..3 {
+            let _ = list(&client).await;
+        }
+        assert_eq!(client.state(), CircuitState::HalfOpen);
+
+        // The probe fails: open again, and a single failure is enough.
+        let _ = list(&client).await;
+        assert_eq!(client.lock().state, CircuitState::Open);
+
+        let mock = MockClient::new();
+        mock.respond(Method::GET, "/templates", MockResponse::server_error())
+            .respond(Method::GET, "/templates", MockResponse::server_error())
+            .respond(Method::GET, "/templates", MockResponse::server_error())
+            .respond(Method::GET, "/templates", templates());
+        let client = CircuitBreakerClient::with_policy(mock, policy(Duration::ZERO));
+        for _ in 0..3 {
+            let _ = list(&client).await;
+        }
+        list(&client).await.expect("probe succeeds");
+        assert_eq!(client.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn open_circuit_is_found_through_wrapping_errors() {
+        let error: CircuitBreakerError<CircuitBreakerError<std::io::Error>> =
+            CircuitBreakerError::Inner {
+                source: CircuitBreakerError::Open {
+                    source: CircuitOpen,
+                },
+            };
+        assert!(matches!(QueryError::client(error), QueryError::CircuitOpen));
+    }
+}
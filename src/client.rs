@@ -6,13 +6,45 @@ use http::{Request, Response, StatusCode};
 use std::error::Error;
 use thiserror::Error;
 
+use crate::PostmarkErrorCode;
+
 /// A trait for providing the necessary information for a single REST API endpoint.
 pub trait Endpoint {
     type Request: serde::Serialize + Send + Sync;
     type Response: serde::de::DeserializeOwned + Send + Sync;
+    /// The token the endpoint authenticates with, [`ServerToken`] or
+    /// [`AccountToken`].
+    ///
+    /// Required since 3.0.0: endpoints implemented outside this crate set it
+    /// to [`ServerToken`] unless they manage the account.
+    type Scope: TokenScope;
 
     /// The path to the endpoint.
     fn endpoint(&self) -> Cow<'static, str>;
+    /// The path to the endpoint with its parameters left as placeholders,
+    /// such as `/templates/{id}`. Telemetry is labeled with this rather than
+    /// [`Endpoint::endpoint`] so the number of distinct values stays bounded.
+    ///
+    /// Defaults to [`Endpoint::endpoint`] without its query string.
+    fn path_template(&self) -> Cow<'static, str> {
+        match self.endpoint() {
+            Cow::Borrowed(path) => Cow::Borrowed(path.split('?').next().unwrap_or_default()),
+            Cow::Owned(mut path) => {
+                if let Some(query_start) = path.find('?') {
+                    path.truncate(query_start);
+                }
+                Cow::Owned(path)
+            }
+        }
+    }
+    /// The message stream the endpoint sends to, when it sends email.
+    /// Messages naming no stream go through
+    /// [`DEFAULT_MESSAGE_STREAM`](crate::api::email::DEFAULT_MESSAGE_STREAM).
+    ///
+    /// Defaults to `None`.
+    fn message_stream(&self) -> Option<Cow<'_, str>> {
+        None
+    }
     /// The body for the endpoint.
     fn body(&self) -> &Self::Request;
     /// The http method for the endpoint
@@ -21,6 +53,38 @@ pub trait Endpoint {
     }
 }
 
+/// The kind of Postmark API token a request authenticates with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// Sent as `X-Postmark-Server-Token`.
+    Server,
+    /// Sent as `X-Postmark-Account-Token`.
+    Account,
+}
+
+/// Marks which token an [`Endpoint`] needs, see [`Endpoint::Scope`].
+pub trait TokenScope: Send + Sync + 'static {
+    const KIND: TokenKind;
+}
+
+/// Scope of endpoints acting on a single server, authenticated with its
+/// server token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServerToken {}
+
+impl TokenScope for ServerToken {
+    const KIND: TokenKind = TokenKind::Server;
+}
+
+/// Scope of endpoints managing the account, such as servers, domains and
+/// sender signatures, authenticated with the account token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountToken {}
+
+impl TokenScope for AccountToken {
+    const KIND: TokenKind = TokenKind::Account;
+}
+
 /// A trait which represents an asynchronous query which may be made to a Postmark client.
 #[async_trait]
 pub trait Query<C> {
@@ -57,17 +121,36 @@ where
         source: http::Error,
     },
     /// API returned non-success status code.
-    #[error("api error: status={status}, error_code={error_code:?}, message={message:?}")]
-    Api {
-        /// HTTP status code.
-        status: StatusCode,
-        /// Postmark error code when present.
-        error_code: Option<i64>,
-        /// Postmark error message when present.
-        message: Option<String>,
-        /// Raw response body.
-        body: Bytes,
-    },
+    #[error("api error: {}", .0)]
+    Api(Box<ApiError>),
+    /// The request was not sent because a
+    /// [`CircuitBreakerClient`](crate::circuit_breaker::CircuitBreakerClient)
+    /// is open.
+    #[error("circuit breaker is open")]
+    CircuitOpen,
+}
+
+/// A non-success response of the Postmark API, see [`QueryError::Api`].
+#[derive(Debug, Clone, Error)]
+#[error(
+    "{method} {path_template}: status={status}, error_code={error_code:?}, message={message:?}"
+)]
+pub struct ApiError {
+    /// HTTP method of the failed request.
+    pub method: http::Method,
+    /// Path of the failed request with its parameters left as placeholders,
+    /// see [`Endpoint::path_template`].
+    pub path_template: String,
+    /// HTTP status code.
+    pub status: StatusCode,
+    /// Postmark error code when present.
+    pub error_code: Option<PostmarkErrorCode>,
+    /// Postmark error message when present.
+    pub message: Option<String>,
+    /// Response headers.
+    pub headers: http::HeaderMap,
+    /// Raw response body.
+    pub body: Bytes,
 }
 
 impl<E> QueryError<E>
@@ -75,9 +158,85 @@ where
     E: Error + Send + Sync + 'static,
 {
     /// Create an API error in a client error.
+    ///
+    /// Errors caused by an open circuit breaker become
+    /// [`QueryError::CircuitOpen`], however deep the
+    /// [`CircuitOpen`](crate::circuit_breaker::CircuitOpen) is in the chain
+    /// of sources.
     pub fn client(source: E) -> Self {
+        if is_circuit_open(&source) {
+            return QueryError::CircuitOpen;
+        }
+
         QueryError::Client { source }
     }
+
+    /// The Postmark error code of an [`QueryError::Api`] error, when present.
+    pub fn error_code(&self) -> Option<PostmarkErrorCode> {
+        match self {
+            QueryError::Api(error) => error.error_code,
+            _ => None,
+        }
+    }
+
+    /// Whether the failed query is worth sending again.
+    ///
+    /// Client errors (the request never got a response) and API errors with
+    /// a `429`, `500`, `502`, `503` or `504` status are considered transient.
+    /// This is the same policy the `retry` feature's `RetryClient` applies.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            QueryError::Client { .. } => true,
+            QueryError::Api(error) => is_retryable_status(error.status),
+            QueryError::Json { .. } | QueryError::Body { .. } | QueryError::CircuitOpen => false,
+        }
+    }
+}
+
+/// Whether a [`CircuitOpen`](crate::circuit_breaker::CircuitOpen) is found in
+/// the chain of sources of `error`.
+pub(crate) fn is_circuit_open(error: &(dyn Error + 'static)) -> bool {
+    let mut cause = Some(error);
+    while let Some(error) = cause {
+        if error.is::<crate::circuit_breaker::CircuitOpen>() {
+            return true;
+        }
+        cause = error.source();
+    }
+    false
+}
+
+/// Statuses for which Postmark may succeed if the same request is sent again.
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Parse a `Retry-After` header, given either in seconds or as an HTTP date.
+#[cfg(any(feature = "retry", feature = "rate-limit"))]
+pub(crate) fn retry_after(headers: &http::HeaderMap) -> Option<std::time::Duration> {
+    use time::OffsetDateTime;
+    use time::format_description::well_known::Rfc2822;
+
+    let value = headers
+        .get(http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(std::time::Duration::from_secs(seconds));
+    }
+
+    let date = OffsetDateTime::parse(value, &Rfc2822).ok()?;
+    let delay = date - OffsetDateTime::now_utc();
+    Some(delay.try_into().unwrap_or_default())
 }
 
 /// Extension method for all endpoints to execute themselves against a client.
@@ -91,48 +250,308 @@ where
     type Result = Result<T::Response, QueryError<C::Error>>;
 
     async fn execute(self, client: &C) -> Self::Result {
-        let method = self.method();
-        let mut req_builder = http::Request::builder()
-            .method(method.clone())
-            .uri(String::from(self.endpoint()))
-            .header("Accept", "application/json");
-
-        let body = match method {
-            http::Method::GET | http::Method::DELETE | http::Method::HEAD => Bytes::new(),
-            _ => {
-                req_builder = req_builder.header("Content-Type", "application/json");
-                serde_json::to_vec(self.body())?.into()
+        let response = send(&self, client).await?;
+        parse_response(&self, response)
+    }
+}
+
+/// Build the request of `endpoint` and send it with `client`.
+pub(crate) async fn send<T, C>(
+    endpoint: &T,
+    client: &C,
+) -> Result<Response<Bytes>, QueryError<C::Error>>
+where
+    T: Endpoint + Send + Sync,
+    C: Client + Send + Sync,
+{
+    let http_req = build_request(endpoint)?;
+
+    #[cfg(feature = "tracing")]
+    let response = crate::instrument::execute(endpoint, client, http_req).await;
+    #[cfg(not(feature = "tracing"))]
+    let response = client.execute(http_req).await;
+
+    response.map_err(QueryError::client)
+}
+
+/// A [`Client`] restricted to endpoints authenticated with a server token.
+///
+/// Executing an endpoint whose [`Endpoint::Scope`] is [`AccountToken`]
+/// against it does not compile:
+///
+/// ```compile_fail
+/// # use postmark::reqwest::PostmarkClient;
+/// # use postmark::{Query, ServerClient};
+/// # use postmark::api::server::CreateServerRequest;
+/// # async fn f() {
+/// let client = ServerClient::new(PostmarkClient::builder().server_token("<token>").build());
+/// let _ = CreateServerRequest::builder().name("x").build().execute(&client).await;
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerClient<C> {
+    inner: C,
+}
+
+/// A [`Client`] restricted to endpoints authenticated with the account token.
+///
+/// Executing an endpoint whose [`Endpoint::Scope`] is [`ServerToken`] against
+/// it does not compile.
+#[derive(Debug, Clone)]
+pub struct AccountClient<C> {
+    inner: C,
+}
+
+macro_rules! scoped_client {
+    ($name:ident, $scope:ty) => {
+        impl<C> $name<C> {
+            pub fn new(inner: C) -> Self {
+                Self { inner }
+            }
+
+            /// The wrapped client.
+            pub fn inner(&self) -> &C {
+                &self.inner
             }
-        };
 
-        let http_req = req_builder.body(body)?;
+            pub fn into_inner(self) -> C {
+                self.inner
+            }
+        }
+
+        #[async_trait]
+        impl<T, C> Query<$name<C>> for T
+        where
+            T: Endpoint<Scope = $scope> + Send + Sync,
+            C: Client + Send + Sync,
+        {
+            type Result = Result<T::Response, QueryError<C::Error>>;
+
+            async fn execute(self, client: &$name<C>) -> Self::Result {
+                Query::<C>::execute(self, &client.inner).await
+            }
+        }
 
-        let response = client.execute(http_req).await.map_err(QueryError::client)?;
+        #[cfg(feature = "blocking")]
+        impl<T, C> crate::blocking::BlockingQuery<$name<C>> for T
+        where
+            T: Endpoint<Scope = $scope>,
+            C: crate::blocking::Client,
+        {
+            type Result = Result<T::Response, QueryError<C::Error>>;
 
-        if !response.status().is_success() {
-            #[derive(serde::Deserialize)]
-            struct PostmarkErrorBody {
-                #[serde(rename = "ErrorCode")]
-                error_code: Option<i64>,
-                #[serde(rename = "Message")]
-                message: Option<String>,
+            fn execute_blocking(self, client: &$name<C>) -> Self::Result {
+                crate::blocking::BlockingQuery::<C>::execute_blocking(self, &client.inner)
             }
+        }
+    };
+}
+
+scoped_client!(ServerClient, ServerToken);
+scoped_client!(AccountClient, AccountToken);
+
+/// Describes the [`Endpoint`] a request was built from.
+///
+/// [`Query`] attaches it to the extensions of every request it hands to a
+/// [`Client`], so client decorators can label what they observe without
+/// parsing paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointInfo {
+    /// See [`Endpoint::path_template`].
+    pub path_template: Cow<'static, str>,
+    /// The token the endpoint needs, see [`Endpoint::Scope`].
+    pub token: TokenKind,
+    /// See [`Endpoint::message_stream`].
+    pub message_stream: Option<String>,
+}
 
-            let body = response.body().clone();
-            let parsed = serde_json::from_slice::<PostmarkErrorBody>(&body).ok();
+/// A server token to send instead of the one the client was built with.
+///
+/// [`ScopedClient`] attaches it to the extensions of every request, and the
+/// bundled clients use it in place of their own server token. It also sets
+/// the `X-Postmark-Server-Token` header itself, so custom [`Client`]s that
+/// ignore the extension still send the scoped token, unless they overwrite
+/// that header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerTokenOverride(pub String);
+
+impl ServerTokenOverride {
+    /// Attach the override to `req` and set its server token header, unless
+    /// the request needs the account token.
+    pub(crate) fn apply(self, req: &mut Request<Bytes>) {
+        let account = req
+            .extensions()
+            .get::<EndpointInfo>()
+            .is_some_and(|info| info.token == TokenKind::Account);
+        if !account {
+            match http::HeaderValue::from_str(&self.0) {
+                Ok(token) => req.headers_mut().insert(SERVER_TOKEN_HEADER, token),
+                // The bundled clients report the invalid token.
+                Err(_) => req.headers_mut().remove(SERVER_TOKEN_HEADER),
+            };
+        }
+        req.extensions_mut().insert(self);
+    }
+}
+
+pub(crate) const SERVER_TOKEN_HEADER: &str = "X-Postmark-Server-Token";
+
+/// How long the bundled clients wait for this request, in place of their
+/// configured timeout.
+///
+/// It covers the whole exchange, from connecting to reading the response
+/// body. Decorators retrying a request apply it to each attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTimeout(pub std::time::Duration);
+
+/// Borrows a [`Client`] and sends every request with another server token.
+///
+/// This is meant for processes sending on behalf of many Postmark servers:
+/// the wrapped client, and its connection pool, is shared by all of them.
+///
+/// ```
+/// # use postmark::reqwest::PostmarkClient;
+/// # use postmark::api::{Body, email::SendEmailRequest};
+/// # async fn send(client: &PostmarkClient, tenant_token: &str) {
+/// let req = SendEmailRequest::builder()
+///     .from("me@example.com")
+///     .to("you@example.com")
+///     .body(Body::text("Hi".into()))
+///     .build();
+/// let resp = client.with_server_token(tenant_token).execute_endpoint(req).await;
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScopedClient<'a, C> {
+    inner: &'a C,
+    server_token: ServerTokenOverride,
+}
 
-            return Err(QueryError::Api {
-                status: response.status(),
-                error_code: parsed.as_ref().and_then(|p| p.error_code),
-                message: parsed.and_then(|p| p.message),
-                body,
-            });
+impl<'a, C> ScopedClient<'a, C> {
+    pub fn new(inner: &'a C, server_token: impl Into<String>) -> Self {
+        Self {
+            inner,
+            server_token: ServerTokenOverride(server_token.into()),
         }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &'a C {
+        self.inner
+    }
+
+    /// The server token requests are sent with.
+    pub fn server_token(&self) -> &str {
+        &self.server_token.0
+    }
 
-        Ok(serde_json::from_slice(response.body())?)
+    fn scope(&self, mut req: Request<Bytes>) -> Request<Bytes> {
+        self.server_token.clone().apply(&mut req);
+        req
     }
 }
 
+impl<C> ScopedClient<'_, C>
+where
+    C: Client + Send + Sync,
+{
+    pub async fn execute_endpoint<T>(&self, request: T) -> Result<T::Response, QueryError<C::Error>>
+    where
+        T: Endpoint + Send + Sync,
+    {
+        request.execute(self).await
+    }
+}
+
+#[async_trait]
+impl<C> Client for ScopedClient<'_, C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = C::Error;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        self.inner.execute(self.scope(req)).await
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<C> crate::blocking::Client for ScopedClient<'_, C>
+where
+    C: crate::blocking::Client,
+{
+    type Error = C::Error;
+
+    fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        self.inner.execute(self.scope(req))
+    }
+}
+
+/// Turn an [`Endpoint`] into the HTTP request handed to a client.
+pub(crate) fn build_request<T, E>(endpoint: &T) -> Result<Request<Bytes>, QueryError<E>>
+where
+    T: Endpoint,
+    E: Error + Send + Sync + 'static,
+{
+    let method = endpoint.method();
+    let mut req_builder = http::Request::builder()
+        .method(method.clone())
+        .uri(String::from(endpoint.endpoint()))
+        .header("Accept", "application/json");
+
+    let body = match method {
+        http::Method::GET | http::Method::DELETE | http::Method::HEAD => Bytes::new(),
+        _ => {
+            req_builder = req_builder.header("Content-Type", "application/json");
+            serde_json::to_vec(endpoint.body())?.into()
+        }
+    };
+
+    let mut req = req_builder.body(body)?;
+    req.extensions_mut().insert(EndpointInfo {
+        path_template: endpoint.path_template(),
+        token: T::Scope::KIND,
+        message_stream: endpoint.message_stream().map(Cow::into_owned),
+    });
+    Ok(req)
+}
+
+/// Decode a client response into the endpoint response, or an API error for
+/// non-success statuses.
+pub(crate) fn parse_response<T, E>(
+    endpoint: &T,
+    response: Response<Bytes>,
+) -> Result<T::Response, QueryError<E>>
+where
+    T: Endpoint,
+    E: Error + Send + Sync + 'static,
+{
+    if !response.status().is_success() {
+        #[derive(serde::Deserialize)]
+        struct PostmarkErrorBody {
+            #[serde(rename = "ErrorCode")]
+            error_code: Option<PostmarkErrorCode>,
+            #[serde(rename = "Message")]
+            message: Option<String>,
+        }
+
+        let body = response.body().clone();
+        let parsed = serde_json::from_slice::<PostmarkErrorBody>(&body).ok();
+
+        return Err(QueryError::Api(Box::new(ApiError {
+            method: endpoint.method(),
+            path_template: endpoint.path_template().into_owned(),
+            status: response.status(),
+            error_code: parsed.as_ref().and_then(|p| p.error_code),
+            message: parsed.and_then(|p| p.message),
+            headers: response.headers().clone(),
+            body,
+        })));
+    }
+
+    Ok(serde_json::from_slice(response.body())?)
+}
+
 /// A trait representing a client which can communicate with a Postmark instance.
 #[async_trait]
 pub trait Client {
@@ -205,6 +624,7 @@ mod tests {
     impl Endpoint for GetEndpoint {
         type Request = NoBody;
         type Response = OkResponse;
+        type Scope = ServerToken;
 
         fn endpoint(&self) -> Cow<'static, str> {
             "/test-get".into()
@@ -224,6 +644,7 @@ mod tests {
     impl Endpoint for DeleteEndpoint {
         type Request = NoBody;
         type Response = OkResponse;
+        type Scope = ServerToken;
 
         fn endpoint(&self) -> Cow<'static, str> {
             "/test-delete".into()
@@ -243,6 +664,7 @@ mod tests {
     impl Endpoint for PostEndpoint {
         type Request = SomeBody;
         type Response = OkResponse;
+        type Scope = ServerToken;
 
         fn endpoint(&self) -> Cow<'static, str> {
             "/test-post".into()
@@ -254,6 +676,42 @@ mod tests {
         }
     }
 
+    struct QueryEndpoint;
+    impl Endpoint for QueryEndpoint {
+        type Request = NoBody;
+        type Response = OkResponse;
+        type Scope = ServerToken;
+
+        fn endpoint(&self) -> Cow<'static, str> {
+            format!("/test-get?count={}&offset={}", 10, 0).into()
+        }
+
+        fn body(&self) -> &Self::Request {
+            static BODY: NoBody = NoBody;
+            &BODY
+        }
+    }
+
+    #[test]
+    fn path_template_defaults_to_path_without_query() {
+        assert_eq!(GetEndpoint.path_template(), "/test-get");
+        assert_eq!(QueryEndpoint.path_template(), "/test-get");
+    }
+
+    #[tokio::test]
+    async fn scoped_client_sets_the_server_token_header() {
+        let client = TestClient::new();
+        let scoped = ScopedClient::new(&client, "tenant-token");
+
+        GetEndpoint.execute(&scoped).await.expect("execute");
+        let request = client.last_request();
+        assert_eq!(request.headers()["X-Postmark-Server-Token"], "tenant-token");
+        assert_eq!(
+            request.extensions().get::<ServerTokenOverride>(),
+            Some(&ServerTokenOverride("tenant-token".into()))
+        );
+    }
+
     #[tokio::test]
     async fn get_request_has_no_json_body_or_content_type() {
         let client = TestClient::new();
@@ -285,6 +743,14 @@ mod tests {
 
         let request = client.last_request();
         assert_eq!(request.method(), http::Method::DELETE);
+        assert_eq!(
+            request.extensions().get::<EndpointInfo>(),
+            Some(&EndpointInfo {
+                path_template: "/test-delete".into(),
+                token: TokenKind::Server,
+                message_stream: None,
+            })
+        );
         assert!(request.body().is_empty());
         assert!(request.headers().get("Content-Type").is_none());
     }
@@ -317,9 +783,10 @@ mod tests {
     impl Endpoint for ErrorEndpoint {
         type Request = NoBody;
         type Response = UnusedResponse;
+        type Scope = ServerToken;
 
         fn endpoint(&self) -> Cow<'static, str> {
-            "/test-error".into()
+            "/test-error?recipient=john@example.com".into()
         }
 
         fn body(&self) -> &Self::Request {
@@ -346,6 +813,49 @@ mod tests {
         }
     }
 
+    struct AccountEndpoint;
+    impl Endpoint for AccountEndpoint {
+        type Request = NoBody;
+        type Response = OkResponse;
+        type Scope = AccountToken;
+
+        fn endpoint(&self) -> Cow<'static, str> {
+            "/test-account".into()
+        }
+
+        fn body(&self) -> &Self::Request {
+            static BODY: NoBody = NoBody;
+            &BODY
+        }
+
+        fn method(&self) -> http::Method {
+            http::Method::GET
+        }
+    }
+
+    #[tokio::test]
+    async fn scoped_clients_tag_requests_with_the_endpoint_token() {
+        let client = ServerClient::new(TestClient::new());
+        GetEndpoint.execute(&client).await.expect("execute");
+        let info = client
+            .inner()
+            .last_request()
+            .extensions()
+            .get::<EndpointInfo>()
+            .cloned();
+        assert_eq!(info.map(|info| info.token), Some(TokenKind::Server));
+
+        let client = AccountClient::new(TestClient::new());
+        AccountEndpoint.execute(&client).await.expect("execute");
+        let info = client
+            .inner()
+            .last_request()
+            .extensions()
+            .get::<EndpointInfo>()
+            .cloned();
+        assert_eq!(info.map(|info| info.token), Some(TokenKind::Account));
+    }
+
     #[tokio::test]
     async fn non_success_status_returns_api_error() {
         let client = ErrorClient {
@@ -356,17 +866,27 @@ mod tests {
         };
 
         let error = ErrorEndpoint.execute(&client).await.expect_err("api error");
+        assert_eq!(
+            error.error_code(),
+            Some(PostmarkErrorCode::InvalidEmailRequest)
+        );
 
         match error {
-            QueryError::Api {
-                status,
-                error_code,
-                message,
-                ..
-            } => {
-                assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
-                assert_eq!(error_code, Some(300));
-                assert_eq!(message.as_deref(), Some("Invalid 'From' address"));
+            QueryError::Api(error) => {
+                assert_eq!(error.method, http::Method::POST);
+                assert_eq!(error.path_template, "/test-error");
+                assert_eq!(error.status, StatusCode::UNPROCESSABLE_ENTITY);
+                assert_eq!(
+                    error.error_code,
+                    Some(PostmarkErrorCode::InvalidEmailRequest)
+                );
+                assert!(
+                    error
+                        .error_code
+                        .is_some_and(PostmarkErrorCode::is_validation)
+                );
+                assert_eq!(error.message.as_deref(), Some("Invalid 'From' address"));
+                assert!(!error.to_string().contains("john@example.com"));
             }
             _ => panic!("expected api error"),
         }
@@ -382,18 +902,34 @@ mod tests {
         let error = ErrorEndpoint.execute(&client).await.expect_err("api error");
 
         match error {
-            QueryError::Api {
-                status,
-                error_code,
-                message,
-                body,
-            } => {
-                assert_eq!(status, StatusCode::BAD_GATEWAY);
-                assert_eq!(error_code, None);
-                assert_eq!(message, None);
-                assert_eq!(body, Bytes::from_static(b"gateway timeout"));
+            QueryError::Api(error) => {
+                assert_eq!(error.status, StatusCode::BAD_GATEWAY);
+                assert_eq!(error.error_code, None);
+                assert_eq!(error.message, None);
+                assert_eq!(error.body, Bytes::from_static(b"gateway timeout"));
             }
             _ => panic!("expected api error"),
         }
     }
+
+    #[tokio::test]
+    async fn api_error_retryable_classification() {
+        for (status, retryable) in [
+            (StatusCode::TOO_MANY_REQUESTS, true),
+            (StatusCode::SERVICE_UNAVAILABLE, true),
+            (StatusCode::UNPROCESSABLE_ENTITY, false),
+            (StatusCode::UNAUTHORIZED, false),
+        ] {
+            let client = ErrorClient {
+                response_status: status,
+                response_body: Bytes::new(),
+            };
+
+            let error = ErrorEndpoint.execute(&client).await.expect_err("api error");
+            assert_eq!(error.is_retryable(), retryable, "status {status}");
+        }
+
+        let error: QueryError<TestClientError> = QueryError::client(TestClientError);
+        assert!(error.is_retryable());
+    }
 }
@@ -0,0 +1,558 @@
+//! A [`Client`] that never contacts Postmark, for staging environments.
+//!
+//! [`DryRunClient`] accepts every send and records it. Sends are checked
+//! locally the way Postmark would reject them (missing sender or recipient,
+//! more than 50 recipients, no body or template, batches over 500
+//! messages) and answered with synthetic responses: a generated `MessageID`
+//! and `ErrorCode: 0` for each accepted message, an `Accepted` bulk request.
+//! As no token is needed, account endpoints can be answered too.
+//!
+//! ```
+//! # async fn f() {
+//! use postmark::Query;
+//! use postmark::api::{Body, email::SendEmailRequest};
+//! use postmark::dry_run::DryRunClient;
+//!
+//! let client = DryRunClient::new();
+//! let rsp = SendEmailRequest::builder()
+//!     .from("me@example.com")
+//!     .to("you@example.com")
+//!     .body(Body::text("Hi".into()))
+//!     .build()
+//!     .execute(&client)
+//!     .await
+//!     .expect("accepted");
+//!
+//! assert!(rsp.message_id.is_some());
+//! assert_eq!(client.requests().len(), 1);
+//! # }
+//! ```
+//!
+//! Other endpoints are answered with `{"ErrorCode": 0, "Message": "OK"}`,
+//! unless a response was set with [`DryRunClient::with_response`]. That
+//! default suits deletes and other actions; endpoints returning a resource
+//! need a response of their own. With the `tracing` feature, every request
+//! is also logged at the `INFO` level.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Method, Request, Response, StatusCode};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::api::bulk::{BulkStatus, SendBulkEmailResponse};
+use crate::api::email::SendEmailResponse;
+use crate::{Client, EndpointInfo, PostmarkErrorCode};
+
+/// Most recipients a message may have, across `To`, `Cc` and `Bcc`.
+const MAX_RECIPIENTS: usize = 50;
+/// Most messages a batch may hold.
+const MAX_BATCH_MESSAGES: usize = 500;
+
+/// How many requests a [`DryRunClient`] keeps by default, see
+/// [`DryRunClient::with_max_recorded`].
+pub const DEFAULT_MAX_RECORDED: usize = 1000;
+
+/// What a sent message must carry besides its addresses.
+#[derive(Clone, Copy)]
+enum Content {
+    Body,
+    Template,
+}
+
+type Rejection = (PostmarkErrorCode, String);
+
+/// A [`Client`] answering requests locally, see the
+/// [module documentation](self).
+///
+/// Clones share their recorded requests. Only the last
+/// [`DEFAULT_MAX_RECORDED`] requests are kept, so a long running process
+/// does not grow without bounds.
+#[derive(Debug, Clone)]
+pub struct DryRunClient {
+    responses: HashMap<(Method, String), Value>,
+    requests: Arc<Mutex<VecDeque<Request<Bytes>>>>,
+    max_recorded: usize,
+}
+
+impl Default for DryRunClient {
+    fn default() -> Self {
+        Self {
+            responses: HashMap::new(),
+            requests: Arc::default(),
+            max_recorded: DEFAULT_MAX_RECORDED,
+        }
+    }
+}
+
+impl DryRunClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the last `max` requests instead of the last
+    /// [`DEFAULT_MAX_RECORDED`]. Zero turns recording off.
+    pub fn with_max_recorded(mut self, max: usize) -> Self {
+        self.max_recorded = max;
+        self
+    }
+
+    /// Answer `method` requests to `path` with `body`.
+    ///
+    /// `path` is matched against the [`Endpoint::path_template`](crate::Endpoint::path_template)
+    /// of the request, such as `/templates/{id}`, or its path when it was
+    /// not built from an endpoint.
+    pub fn with_response(mut self, method: Method, path: &str, body: impl Serialize) -> Self {
+        let body = serde_json::to_value(body).expect("dry run response serializes to JSON");
+        self.responses.insert((method, path.to_string()), body);
+        self
+    }
+
+    /// The requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<Request<Bytes>> {
+        self.lock().iter().cloned().collect()
+    }
+
+    /// Forget the recorded requests.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, VecDeque<Request<Bytes>>> {
+        self.requests.lock().expect("dry run requests poisoned")
+    }
+
+    fn record(&self, req: Request<Bytes>) {
+        if self.max_recorded == 0 {
+            return;
+        }
+        let mut requests = self.lock();
+        while requests.len() >= self.max_recorded {
+            requests.pop_front();
+        }
+        requests.push_back(req);
+    }
+
+    fn respond(&self, req: Request<Bytes>) -> Response<Bytes> {
+        let path = match req.extensions().get::<EndpointInfo>() {
+            Some(info) => info.path_template.to_string(),
+            None => req.uri().path().to_string(),
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            http.method = %req.method(),
+            postmark.endpoint = %path,
+            body.len = req.body().len(),
+            "postmark dry run request"
+        );
+
+        let rsp = match self.responses.get(&(req.method().clone(), path.clone())) {
+            Some(body) => json(StatusCode::OK, body),
+            None => synthesize(req.method(), &path, req.body()).unwrap_or_else(|| {
+                json(
+                    StatusCode::OK,
+                    serde_json::json!({ "ErrorCode": PostmarkErrorCode::Ok, "Message": "OK" }),
+                )
+            }),
+        };
+
+        self.record(req);
+        rsp
+    }
+}
+
+#[async_trait]
+impl Client for DryRunClient {
+    type Error = Infallible;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        Ok(self.respond(req))
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl crate::blocking::Client for DryRunClient {
+    type Error = Infallible;
+
+    fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        Ok(self.respond(req))
+    }
+}
+
+/// The response Postmark would give to a `POST` to `path`, when `path` is a
+/// send endpoint.
+fn synthesize(method: &Method, path: &str, body: &[u8]) -> Option<Response<Bytes>> {
+    let send = method == Method::POST
+        && matches!(
+            path,
+            "/email"
+                | "/email/withTemplate"
+                | "/email/batch"
+                | "/email/batchWithTemplates"
+                | "/email/bulk"
+        );
+    if !send {
+        return None;
+    }
+    let Ok(body) = serde_json::from_slice::<Value>(body) else {
+        return Some(rejected((
+            PostmarkErrorCode::InvalidJson,
+            "Invalid JSON".into(),
+        )));
+    };
+
+    Some(match path {
+        "/email" => single(&body, Content::Body),
+        "/email/withTemplate" => single(&body, Content::Template),
+        "/email/batch" => batch(body.as_array(), Content::Body),
+        "/email/batchWithTemplates" => batch(
+            body.get("Messages").and_then(Value::as_array),
+            Content::Template,
+        ),
+        _ => bulk(&body),
+    })
+}
+
+fn single(message: &Value, content: Content) -> Response<Bytes> {
+    match validate(message, content) {
+        Ok(()) => json(StatusCode::OK, accepted(message)),
+        Err(rejection) => rejected(rejection),
+    }
+}
+
+fn batch(messages: Option<&Vec<Value>>, content: Content) -> Response<Bytes> {
+    let Some(messages) = messages else {
+        return rejected((
+            PostmarkErrorCode::IncompatibleJson,
+            "Expected a list of messages".into(),
+        ));
+    };
+    if messages.len() > MAX_BATCH_MESSAGES {
+        return rejected((
+            PostmarkErrorCode::TooManyBatchMessages,
+            format!("Batch may not hold more than {MAX_BATCH_MESSAGES} messages"),
+        ));
+    }
+
+    // Postmark answers a batch with `200 OK` and one result per message.
+    let results: Vec<_> = messages
+        .iter()
+        .map(|message| match validate(message, content) {
+            Ok(()) => accepted(message),
+            Err((error_code, message)) => SendEmailResponse {
+                error_code,
+                message,
+                ..Default::default()
+            },
+        })
+        .collect();
+    json(StatusCode::OK, results)
+}
+
+fn bulk(body: &Value) -> Response<Bytes> {
+    let check = || {
+        require_address(body, "From")?;
+        let content = if body.get("TemplateId").is_some() || body.get("TemplateAlias").is_some() {
+            Content::Template
+        } else {
+            Content::Body
+        };
+        require_content(body, content)?;
+
+        let messages = body
+            .get("Messages")
+            .and_then(Value::as_array)
+            .filter(|messages| !messages.is_empty())
+            .ok_or_else(|| {
+                (
+                    PostmarkErrorCode::InvalidEmailRequest,
+                    "Provide at least one message".to_string(),
+                )
+            })?;
+        messages.iter().try_for_each(require_recipients)
+    };
+
+    match check() {
+        Ok(()) => json(
+            StatusCode::OK,
+            SendBulkEmailResponse {
+                id: Some(message_id()),
+                status: Some(BulkStatus::Accepted),
+                submitted_at: Some(now()),
+                ..Default::default()
+            },
+        ),
+        Err(rejection) => rejected(rejection),
+    }
+}
+
+fn validate(message: &Value, content: Content) -> Result<(), Rejection> {
+    require_address(message, "From")?;
+    require_recipients(message)?;
+    require_content(message, content)
+}
+
+fn require_address(message: &Value, field: &str) -> Result<(), Rejection> {
+    match message.get(field).and_then(Value::as_str) {
+        Some(address) if address.contains('@') => Ok(()),
+        _ => Err((
+            PostmarkErrorCode::InvalidEmailRequest,
+            format!("Invalid '{field}' address"),
+        )),
+    }
+}
+
+fn require_recipients(message: &Value) -> Result<(), Rejection> {
+    require_address(message, "To")?;
+
+    let recipients: usize = ["To", "Cc", "Bcc"]
+        .iter()
+        .filter_map(|field| message.get(field).and_then(Value::as_str))
+        .map(count_addresses)
+        .sum();
+    if recipients > MAX_RECIPIENTS {
+        return Err((
+            PostmarkErrorCode::InvalidEmailRequest,
+            format!("A message may not have more than {MAX_RECIPIENTS} recipients"),
+        ));
+    }
+    Ok(())
+}
+
+/// The addresses of a list such as `"Doe, John" <j@example.com>, a@example.com`,
+/// counted by their `@` outside quoted display names.
+fn count_addresses(addresses: &str) -> usize {
+    let mut quoted = false;
+    let mut escaped = false;
+    let mut count = 0;
+    for c in addresses.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            '@' if !quoted => count += 1,
+            _ => {}
+        }
+    }
+    count
+}
+
+fn require_content(message: &Value, content: Content) -> Result<(), Rejection> {
+    let present = |field: &str| {
+        message
+            .get(field)
+            .is_some_and(|value| !value.is_null() && value != "")
+    };
+
+    match content {
+        Content::Body if present("HtmlBody") || present("TextBody") => Ok(()),
+        Content::Body => Err((
+            PostmarkErrorCode::InvalidEmailRequest,
+            "Provide either email TextBody or HtmlBody or both".into(),
+        )),
+        Content::Template if present("TemplateId") || present("TemplateAlias") => Ok(()),
+        Content::Template => Err((
+            PostmarkErrorCode::InvalidEmailRequest,
+            "Provide either TemplateId or TemplateAlias".into(),
+        )),
+    }
+}
+
+fn accepted(message: &Value) -> SendEmailResponse {
+    SendEmailResponse {
+        to: message.get("To").and_then(Value::as_str).map(Into::into),
+        submitted_at: Some(now()),
+        message_id: Some(message_id()),
+        error_code: PostmarkErrorCode::Ok,
+        message: "OK".into(),
+    }
+}
+
+fn rejected((error_code, message): Rejection) -> Response<Bytes> {
+    json(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        serde_json::json!({ "ErrorCode": error_code, "Message": message }),
+    )
+}
+
+fn json(status: StatusCode, body: impl Serialize) -> Response<Bytes> {
+    let body = serde_json::to_vec(&body).expect("dry run response serializes to JSON");
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(body.into())
+        .expect("dry run response is a valid http response")
+}
+
+/// A random version 4 UUID, like Postmark's `MessageID`s.
+fn message_id() -> String {
+    let bits = fastrand::u128(..);
+    // Set the version to 4 and the variant to RFC 4122.
+    let bits = (bits & !(0xf << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62);
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        bits >> 96,
+        (bits >> 80) & 0xffff,
+        (bits >> 64) & 0xffff,
+        (bits >> 48) & 0xffff,
+        bits & 0xffff_ffff_ffff
+    )
+}
+
+fn now() -> String {
+    time::OffsetDateTime::now_utc()
+        .format(&time::format_description::well_known::Rfc3339)
+        .expect("current time formats as RFC 3339")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Body;
+    use crate::api::bulk::{BulkMessage, SendBulkEmailRequest};
+    use crate::api::email::{SendEmailBatchRequest, SendEmailRequest};
+    use crate::api::server::{DeleteServerRequest, GetServerRequest};
+    use crate::{Query, QueryError};
+
+    fn email(to: &str) -> SendEmailRequest {
+        SendEmailRequest::builder()
+            .from("me@example.com")
+            .to(to)
+            .body(Body::text("Hi".into()))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_sends_with_synthetic_responses() {
+        let client = DryRunClient::new();
+
+        let rsp = email("you@example.com")
+            .execute(&client)
+            .await
+            .expect("accepted");
+        assert!(rsp.error_code.is_ok());
+        assert_eq!(rsp.to.as_deref(), Some("you@example.com"));
+        assert_eq!(rsp.message_id.as_ref().map(String::len), Some(36));
+
+        let batch: SendEmailBatchRequest = vec![email("a@example.com"), email("not an address")];
+        let rsp = batch.execute(&client).await.expect("batch");
+        assert!(rsp[0].error_code.is_ok());
+        assert_eq!(rsp[1].error_code, PostmarkErrorCode::InvalidEmailRequest);
+
+        assert_eq!(client.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_sends_like_postmark() {
+        let client = DryRunClient::new();
+
+        let mut invalid = email("you@example.com");
+        invalid.to = (0..51)
+            .map(|i| format!("r{i}@example.com"))
+            .collect::<Vec<_>>()
+            .join(",");
+        let error = invalid.execute(&client).await.expect_err("rejected");
+        assert!(matches!(
+            error,
+            QueryError::Api(error)
+                if error.status == StatusCode::UNPROCESSABLE_ENTITY
+                    && error.error_code == Some(PostmarkErrorCode::InvalidEmailRequest)
+        ));
+
+        let batch: SendEmailBatchRequest = vec![email("you@example.com"); 501];
+        let error = batch.execute(&client).await.expect_err("rejected");
+        assert_eq!(
+            error.error_code(),
+            Some(PostmarkErrorCode::TooManyBatchMessages)
+        );
+    }
+
+    #[tokio::test]
+    async fn bulk_and_account_endpoints() {
+        let client = DryRunClient::new().with_response(
+            Method::GET,
+            "/servers/{id}",
+            serde_json::json!({ "ID": 1, "Name": "Staging", "ApiTokens": ["token"] }),
+        );
+
+        let rsp = SendBulkEmailRequest::builder()
+            .from("me@example.com".to_string())
+            .messages(vec![BulkMessage {
+                to: "you@example.com".into(),
+                ..Default::default()
+            }])
+            .subject("Hi")
+            .text_body("Hi")
+            .build()
+            .execute(&client)
+            .await
+            .expect("bulk");
+        assert_eq!(rsp.status, Some(BulkStatus::Accepted));
+
+        let server = GetServerRequest::builder()
+            .server_id(1)
+            .build()
+            .execute(&client)
+            .await
+            .expect("server");
+        assert_eq!(server.name, "Staging");
+
+        let deleted = DeleteServerRequest::builder()
+            .server_id(1)
+            .build()
+            .execute(&client)
+            .await
+            .expect("default response");
+        assert!(deleted.error_code.is_ok());
+    }
+
+    #[test]
+    fn counts_addresses_with_quoted_display_names() {
+        assert_eq!(count_addresses("a@example.com, b@example.com"), 2);
+        assert_eq!(
+            count_addresses(r#""Doe, John" <j@example.com>, "a@b, \"c\"" <a@example.com>"#),
+            2
+        );
+        assert_eq!(count_addresses(""), 0);
+    }
+
+    #[test]
+    fn message_ids_are_version_4_uuids() {
+        let id = message_id();
+        let groups: Vec<_> = id.split('-').map(str::len).collect();
+        assert_eq!(groups, [8, 4, 4, 4, 12]);
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(id, message_id());
+    }
+
+    #[tokio::test]
+    async fn keeps_the_last_requests_and_unique_ids() {
+        let client = DryRunClient::new().with_max_recorded(2);
+
+        let mut ids = std::collections::HashSet::new();
+        for to in ["a@example.com", "b@example.com", "c@example.com"] {
+            let rsp = email(to).execute(&client).await.expect("accepted");
+            assert!(ids.insert(rsp.message_id.expect("message id")));
+        }
+
+        let recipients: Vec<_> = client
+            .requests()
+            .iter()
+            .map(|req| serde_json::from_slice::<Value>(req.body()).expect("json")["To"].clone())
+            .collect();
+        assert_eq!(recipients, ["b@example.com", "c@example.com"]);
+
+        let client = DryRunClient::new().with_max_recorded(0);
+        email("a@example.com")
+            .execute(&client)
+            .await
+            .expect("accepted");
+        assert!(client.requests().is_empty());
+    }
+}
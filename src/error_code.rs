@@ -0,0 +1,230 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+macro_rules! error_codes {
+    ($($(#[$meta:meta])* $variant:ident = $code:literal,)*) => {
+        /// An `ErrorCode` returned by Postmark.
+        ///
+        /// See the [API error codes](https://postmarkapp.com/developer/api/overview#error-codes)
+        /// documentation. Codes this crate does not know about are kept as
+        /// [`PostmarkErrorCode::Unknown`].
+        ///
+        /// Codes compare by their numeric value, so `Unknown(406)` equals
+        /// [`PostmarkErrorCode::InactiveRecipient`].
+        #[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+        #[serde(from = "i64", into = "i64")]
+        #[non_exhaustive]
+        pub enum PostmarkErrorCode {
+            /// No error, returned alongside successful sends.
+            #[default]
+            Ok,
+            $($(#[$meta])* $variant,)*
+            /// A code without a variant yet. Build it with
+            /// [`PostmarkErrorCode::from`] to get the variant of known codes.
+            Unknown(i64),
+        }
+
+        impl From<i64> for PostmarkErrorCode {
+            fn from(code: i64) -> Self {
+                match code {
+                    0 => Self::Ok,
+                    $($code => Self::$variant,)*
+                    code => Self::Unknown(code),
+                }
+            }
+        }
+
+        impl From<PostmarkErrorCode> for i64 {
+            fn from(code: PostmarkErrorCode) -> Self {
+                match code {
+                    PostmarkErrorCode::Ok => 0,
+                    $(PostmarkErrorCode::$variant => $code,)*
+                    PostmarkErrorCode::Unknown(code) => code,
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    /// `10`: bad or missing API token.
+    BadOrMissingApiToken = 10,
+    /// `100`: Postmark is down for maintenance.
+    Maintenance = 100,
+    /// `300`: invalid email request, such as a malformed address.
+    InvalidEmailRequest = 300,
+    /// `400`: the sender signature was not found.
+    SenderSignatureNotFound = 400,
+    /// `401`: the sender signature is not confirmed.
+    SenderSignatureNotConfirmed = 401,
+    /// `402`: the request body is not valid JSON.
+    InvalidJson = 402,
+    /// `403`: the JSON does not match the request type.
+    IncompatibleJson = 403,
+    /// `405`: the account ran out of credits.
+    NotAllowedToSend = 405,
+    /// `406`: the recipient was marked as inactive after a hard bounce or
+    /// spam complaint.
+    InactiveRecipient = 406,
+    /// `407`: the bounce was not found.
+    BounceNotFound = 407,
+    /// `408`: the bounce query is invalid.
+    BounceQueryException = 408,
+    /// `409`: the request needs a JSON body and `Content-Type`.
+    JsonRequired = 409,
+    /// `410`: a batch holds more than 500 messages.
+    TooManyBatchMessages = 410,
+    /// `411`: an attachment has a forbidden file type.
+    ForbiddenAttachmentType = 411,
+    /// `412`: the account is pending approval and can only send to its own
+    /// domain.
+    AccountIsPending = 412,
+    /// `413`: the account may not send.
+    AccountMayNotSend = 413,
+    /// `429`: too many requests.
+    RateLimitExceeded = 429,
+    /// `701`: the message was not found.
+    MessageNotFound = 701,
+    /// `1100`: the template query is invalid.
+    TemplateQueryException = 1100,
+    /// `1101`: the template was not found.
+    TemplateNotFound = 1101,
+    /// `1105`: the server reached its template limit.
+    TemplateLimitWouldBeExceeded = 1105,
+    /// `1109`: no template data was returned.
+    NoTemplateDataReturned = 1109,
+    /// `1120`: a required template field is missing.
+    RequiredTemplateFieldMissing = 1120,
+    /// `1121`: a template field is too large.
+    TemplateFieldTooLarge = 1121,
+    /// `1122`: a template field is invalid.
+    InvalidTemplateField = 1122,
+    /// `1123`: the template holds an invalid field.
+    InvalidFieldInTemplate = 1123,
+    /// `1125`: the template's content failed to render.
+    TemplateRenderingFailed = 1125,
+}
+
+impl PostmarkErrorCode {
+    /// The numeric code.
+    pub fn code(self) -> i64 {
+        self.into()
+    }
+
+    /// Whether the code is `0`, meaning no error.
+    pub fn is_ok(self) -> bool {
+        self == Self::Ok
+    }
+
+    /// Whether the recipient is inactive, so sending to it again will fail
+    /// the same way until it is reactivated.
+    pub fn is_inactive_recipient(self) -> bool {
+        self == Self::InactiveRecipient
+    }
+
+    /// Whether the request was rejected because of its token.
+    pub fn is_auth(self) -> bool {
+        self == Self::BadOrMissingApiToken
+    }
+
+    /// Whether the request itself is invalid and must be changed before it
+    /// can succeed.
+    pub fn is_validation(self) -> bool {
+        matches!(
+            self,
+            Self::InvalidEmailRequest
+                | Self::InvalidJson
+                | Self::IncompatibleJson
+                | Self::JsonRequired
+                | Self::TooManyBatchMessages
+                | Self::ForbiddenAttachmentType
+                | Self::RequiredTemplateFieldMissing
+                | Self::TemplateFieldTooLarge
+                | Self::InvalidTemplateField
+                | Self::InvalidFieldInTemplate
+                | Self::TemplateRenderingFailed
+        )
+    }
+
+    /// Whether Postmark asked to slow down.
+    pub fn is_rate_limited(self) -> bool {
+        self == Self::RateLimitExceeded
+    }
+}
+
+impl PartialEq for PostmarkErrorCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.code() == other.code()
+    }
+}
+
+impl Eq for PostmarkErrorCode {}
+
+impl std::hash::Hash for PostmarkErrorCode {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.code().hash(state);
+    }
+}
+
+impl PartialEq<i64> for PostmarkErrorCode {
+    fn eq(&self, other: &i64) -> bool {
+        self.code() == *other
+    }
+}
+
+impl fmt::Display for PostmarkErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_roundtrip_through_json() {
+        for code in [0, 10, 300, 406, 429, 1101, 1125, 12345] {
+            let parsed: PostmarkErrorCode =
+                serde_json::from_str(&code.to_string()).expect("deserialize");
+            assert_eq!(parsed, code);
+            assert_eq!(
+                serde_json::to_string(&parsed).expect("serialize"),
+                code.to_string()
+            );
+        }
+
+        assert_eq!(PostmarkErrorCode::from(0), PostmarkErrorCode::Ok);
+        assert_eq!(
+            PostmarkErrorCode::from(406),
+            PostmarkErrorCode::InactiveRecipient
+        );
+        assert_eq!(
+            PostmarkErrorCode::from(12345),
+            PostmarkErrorCode::Unknown(12345)
+        );
+    }
+
+    #[test]
+    fn classification() {
+        assert!(PostmarkErrorCode::from(406).is_inactive_recipient());
+        assert!(PostmarkErrorCode::from(10).is_auth());
+        assert!(PostmarkErrorCode::from(300).is_validation());
+        assert!(PostmarkErrorCode::from(429).is_rate_limited());
+        assert!(!PostmarkErrorCode::from(1101).is_validation());
+    }
+
+    #[test]
+    fn unknown_known_codes_equal_their_variant() {
+        let unknown = PostmarkErrorCode::Unknown(406);
+        assert_eq!(unknown, PostmarkErrorCode::InactiveRecipient);
+        assert_eq!(unknown, 406);
+        assert!(unknown.is_inactive_recipient());
+        assert_ne!(PostmarkErrorCode::Unknown(0), PostmarkErrorCode::Unknown(1));
+
+        let codes: std::collections::HashSet<_> =
+            [unknown, PostmarkErrorCode::InactiveRecipient].into();
+        assert_eq!(codes.len(), 1);
+    }
+}
@@ -0,0 +1,254 @@
+//! Gzip compression of request bodies, and decoding of gzip responses.
+//!
+//! [`GzipClient`] compresses request bodies larger than a threshold and
+//! sends them with `Content-Encoding: gzip`, which mostly pays off for
+//! batch and bulk sends carrying attachments. Smaller bodies are sent as
+//! is, as compressing them costs more than it saves.
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use postmark::gzip::GzipClient;
+//!
+//! let client = GzipClient::with_threshold(PostmarkClient::default(), 64 * 1024);
+//! ```
+//!
+//! With the `gzip` feature, the bundled clients also ask for compressed
+//! responses and decode them. [`GzipClient`] decodes the ones its wrapped
+//! client leaves encoded, so custom [`Client`]s get them decoded too.
+
+use std::io::{self, Read, Write};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use http::header::{CONTENT_ENCODING, CONTENT_LENGTH, HeaderValue};
+use http::{Request, Response};
+use thiserror::Error;
+
+use crate::Client;
+
+/// Bodies of at least this many bytes are compressed by [`GzipClient::new`].
+pub const DEFAULT_THRESHOLD: usize = 32 * 1024;
+
+/// Responses decoding to more than this many bytes are rejected, so a small
+/// compressed body cannot exhaust memory.
+pub const MAX_DECODED_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum GzipError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// The wrapped client failed.
+    #[error("{}", source)]
+    Inner { source: E },
+    /// A body could not be compressed or decompressed.
+    #[error("gzip error: {}", source)]
+    Io { source: io::Error },
+}
+
+/// Wraps a [`Client`] and compresses large request bodies, see the
+/// [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct GzipClient<C> {
+    inner: C,
+    threshold: usize,
+}
+
+impl<C> GzipClient<C> {
+    /// Compress bodies of at least [`DEFAULT_THRESHOLD`] bytes.
+    pub fn new(inner: C) -> Self {
+        Self::with_threshold(inner, DEFAULT_THRESHOLD)
+    }
+
+    /// Compress bodies of at least `threshold` bytes.
+    pub fn with_threshold(inner: C, threshold: usize) -> Self {
+        Self { inner, threshold }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    fn compress(&self, mut req: Request<Bytes>) -> io::Result<Request<Bytes>> {
+        if req.body().len() < self.threshold || req.headers().contains_key(CONTENT_ENCODING) {
+            return Ok(req);
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(req.body())?;
+        let body = Bytes::from(encoder.finish()?);
+
+        let headers = req.headers_mut();
+        headers.insert(CONTENT_ENCODING, HeaderValue::from_static("gzip"));
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+        *req.body_mut() = body;
+        Ok(req)
+    }
+}
+
+/// Decode a response sent with `Content-Encoding: gzip`, and drop the
+/// header. Other responses are returned untouched.
+///
+/// Fails when the decoded body is larger than [`MAX_DECODED_SIZE`].
+pub(crate) fn decode_response(rsp: Response<Bytes>) -> io::Result<Response<Bytes>> {
+    decode_response_up_to(rsp, MAX_DECODED_SIZE)
+}
+
+fn decode_response_up_to(mut rsp: Response<Bytes>, max_size: u64) -> io::Result<Response<Bytes>> {
+    let gzipped = rsp
+        .headers()
+        .get(CONTENT_ENCODING)
+        .is_some_and(|encoding| encoding.as_bytes().eq_ignore_ascii_case(b"gzip"));
+    if !gzipped {
+        return Ok(rsp);
+    }
+
+    let mut body = Vec::new();
+    GzDecoder::new(rsp.body().as_ref())
+        .take(max_size + 1)
+        .read_to_end(&mut body)?;
+    if body.len() as u64 > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("gzip response decodes to more than {max_size} bytes"),
+        ));
+    }
+
+    let headers = rsp.headers_mut();
+    headers.remove(CONTENT_ENCODING);
+    headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+    *rsp.body_mut() = body.into();
+    Ok(rsp)
+}
+
+#[async_trait]
+impl<C> Client for GzipClient<C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = GzipError<C::Error>;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let req = self
+            .compress(req)
+            .map_err(|source| GzipError::Io { source })?;
+        let rsp = self
+            .inner
+            .execute(req)
+            .await
+            .map_err(|source| GzipError::Inner { source })?;
+        decode_response(rsp).map_err(|source| GzipError::Io { source })
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<C> crate::blocking::Client for GzipClient<C>
+where
+    C: crate::blocking::Client,
+{
+    type Error = GzipError<C::Error>;
+
+    fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let req = self
+            .compress(req)
+            .map_err(|source| GzipError::Io { source })?;
+        let rsp = self
+            .inner
+            .execute(req)
+            .map_err(|source| GzipError::Inner { source })?;
+        decode_response(rsp).map_err(|source| GzipError::Io { source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::{Method, StatusCode};
+
+    use super::*;
+    use crate::Query;
+    use crate::api::Body;
+    use crate::api::email::{SendEmailBatchRequest, SendEmailRequest, SendEmailResponse};
+    use crate::testing::{MockClient, MockResponse};
+
+    fn gunzip(body: &[u8]) -> Vec<u8> {
+        let mut decoded = Vec::new();
+        GzDecoder::new(body)
+            .read_to_end(&mut decoded)
+            .expect("gzip body");
+        decoded
+    }
+
+    fn batch(size: usize) -> SendEmailBatchRequest {
+        (0..size)
+            .map(|i| {
+                SendEmailRequest::builder()
+                    .from("me@example.com")
+                    .to(format!("you-{i}@example.com"))
+                    .body(Body::text("x".repeat(100)))
+                    .build()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn compresses_bodies_over_the_threshold() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::ok(Vec::<SendEmailResponse>::new()),
+        );
+        let client = GzipClient::with_threshold(mock.clone(), 1024);
+
+        batch(1).execute(&client).await.expect("small batch");
+        batch(50).execute(&client).await.expect("large batch");
+
+        let requests = mock.requests();
+        assert!(requests[0].headers().get(CONTENT_ENCODING).is_none());
+
+        assert_eq!(requests[1].headers()[CONTENT_ENCODING], "gzip");
+        let sent: SendEmailBatchRequest =
+            serde_json::from_slice(&gunzip(requests[1].body())).expect("batch json");
+        assert_eq!(sent, batch(50));
+    }
+
+    #[test]
+    fn decodes_gzip_responses() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(br#"{"ok":true}"#).expect("compress");
+        let rsp = Response::builder()
+            .status(StatusCode::OK)
+            .header(CONTENT_ENCODING, "gzip")
+            .body(Bytes::from(encoder.finish().expect("compress")))
+            .expect("response");
+
+        let rsp = decode_response(rsp).expect("decoded");
+        assert!(rsp.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(rsp.body().as_ref(), br#"{"ok":true}"#);
+    }
+
+    #[test]
+    fn rejects_responses_decoding_past_the_limit() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&[0; 1025]).expect("compress");
+        let encoded = Bytes::from(encoder.finish().expect("compress"));
+        let rsp = || {
+            Response::builder()
+                .header(CONTENT_ENCODING, "gzip")
+                .body(encoded.clone())
+                .expect("response")
+        };
+
+        decode_response_up_to(rsp(), 1025).expect("at the limit");
+        let error = decode_response_up_to(rsp(), 1024).expect_err("too large");
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,314 @@
+use crate::transport::prepare_request;
+use crate::{Client, POSTMARK_API_URL};
+use crate::{Endpoint, Query, QueryError, RequestTimeout, ScopedClient};
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Request, Response};
+use http_body_util::{BodyExt, Full};
+use hyper_util::client::legacy::connect::Connect;
+use thiserror::Error;
+use typed_builder::TypedBuilder;
+
+/// The pooled hyper client used by [`HyperPostmarkClient`].
+pub type HyperClient<C> = hyper_util::client::legacy::Client<C, Full<Bytes>>;
+
+/// A representation of the asynchronous Postmark API for a single user.
+/// Separate users should use separate instances of this.
+///
+/// A hyper based [`Client`]. It reuses the connection pool and connector of
+/// the hyper client it is given, so TLS is set up by the caller (for example
+/// with `hyper-rustls` or `hyper-tls`). A [`RequestTimeout`] on a request
+/// bounds the whole exchange, from connecting to reading the response body.
+///
+/// ```
+/// # use postmark::hyper::HyperPostmarkClient;
+/// use hyper_util::client::legacy::{Client, connect::HttpConnector};
+/// use hyper_util::rt::TokioExecutor;
+///
+/// let client = HyperPostmarkClient::builder()
+///   .base_url("http://localhost:8080")
+///   .server_token("<sometoken>")
+///   .client(Client::builder(TokioExecutor::new()).build(HttpConnector::new()))
+///   .build();
+/// ```
+#[derive(TypedBuilder, Clone)]
+pub struct HyperPostmarkClient<C> {
+    #[builder(default, setter(into, strip_option))]
+    pub server_token: Option<String>,
+    #[builder(default, setter(into, strip_option))]
+    pub account_token: Option<String>,
+    #[builder(default=POSTMARK_API_URL.into(), setter(into))]
+    pub base_url: String,
+    client: HyperClient<C>,
+}
+
+impl<C> std::fmt::Debug for HyperPostmarkClient<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Self {
+            server_token: ref _server_token,
+            account_token: ref _account_token,
+            base_url: ref _base_url,
+            client: ref _client,
+        } = *self;
+
+        let mut builder = f.debug_struct("HyperPostmarkClient");
+        builder.field("server_token", &_server_token.as_ref().map(|_| "***"));
+        builder.field("account_token", &_account_token.as_ref().map(|_| "***"));
+        builder.field("base_url", _base_url);
+        builder.finish()
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum HyperPostmarkClientError {
+    #[error("error setting auth header: {}", source)]
+    AuthError {
+        #[from]
+        source: http::header::InvalidHeaderValue,
+    },
+    #[error("communication with postmark: {}", source)]
+    Communication {
+        #[from]
+        source: hyper_util::client::legacy::Error,
+    },
+    #[error("reading response body: {}", source)]
+    Body {
+        #[from]
+        source: hyper::Error,
+    },
+    #[error("`http` error: {}", source)]
+    Http {
+        #[from]
+        source: http::Error,
+    },
+    #[error("`Url` error: {}", source)]
+    UrlParseError {
+        #[from]
+        source: url::ParseError,
+    },
+    #[error("invalid uri: {}", source)]
+    InvalidUri {
+        #[from]
+        source: http::uri::InvalidUri,
+    },
+    #[error("no response after {timeout:?}")]
+    Timeout { timeout: std::time::Duration },
+    /// Only returned with the `gzip` feature.
+    #[error("decoding gzip response: {}", source)]
+    Gzip {
+        #[from]
+        source: std::io::Error,
+    },
+}
+
+#[async_trait]
+impl<C> Client for HyperPostmarkClient<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    type Error = HyperPostmarkClientError;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        #[cfg_attr(not(feature = "gzip"), allow(unused_mut))]
+        let mut req = prepare_request::<Self::Error>(
+            req,
+            self.server_token.as_deref(),
+            self.account_token.as_deref(),
+            &self.base_url,
+        )?;
+
+        #[cfg(feature = "gzip")]
+        req.headers_mut()
+            .entry(http::header::ACCEPT_ENCODING)
+            .or_insert(http::HeaderValue::from_static("gzip"));
+
+        let timeout = req.extensions().get::<RequestTimeout>().copied();
+        let exchange = async {
+            let hyper_rsp = self.client.request(req.map(Full::new)).await?;
+            let (parts, body) = hyper_rsp.into_parts();
+            let body = body.collect().await?.to_bytes();
+            Ok::<_, Self::Error>(Response::from_parts(parts, body))
+        };
+        let rsp = match timeout {
+            Some(RequestTimeout(timeout)) => tokio::time::timeout(timeout, exchange)
+                .await
+                .map_err(|_| HyperPostmarkClientError::Timeout { timeout })??,
+            None => exchange.await?,
+        };
+
+        #[cfg(feature = "gzip")]
+        let rsp = crate::gzip::decode_response(rsp)?;
+
+        Ok(rsp)
+    }
+}
+
+impl<C> HyperPostmarkClient<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    pub async fn execute_endpoint<T>(
+        &self,
+        request: T,
+    ) -> Result<T::Response, QueryError<HyperPostmarkClientError>>
+    where
+        T: Endpoint + Send + Sync,
+    {
+        request.execute(self).await
+    }
+
+    /// Borrow this client to send requests with another server token,
+    /// sharing its connection pool.
+    pub fn with_server_token(&self, server_token: impl Into<String>) -> ScopedClient<'_, Self> {
+        ScopedClient::new(self, server_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httptest::matchers::{all_of, contains, key, not, request};
+    use httptest::{Expectation, Server, responders::*};
+    use hyper_util::client::legacy::connect::HttpConnector;
+    use hyper_util::rt::TokioExecutor;
+    use serde_json::json;
+
+    use super::*;
+    use crate::PostmarkErrorCode;
+    use crate::api::Body;
+    use crate::api::email::SendEmailRequest;
+
+    fn client(server: &Server) -> HyperPostmarkClient<HttpConnector> {
+        HyperPostmarkClient::builder()
+            .base_url(server.url("/").to_string())
+            .server_token("server-token")
+            .client(
+                hyper_util::client::legacy::Client::builder(TokioExecutor::new())
+                    .build(HttpConnector::new()),
+            )
+            .build()
+    }
+
+    #[tokio::test]
+    async fn send_email_over_hyper() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/email"),
+                request::headers(contains(("x-postmark-server-token", "server-token"))),
+                request::headers(not(contains(key("x-postmark-account-token")))),
+            ])
+            .respond_with(json_encoded(json!({
+                "To": "receiver@example.com",
+                "SubmittedAt": "2014-02-17T07:25:01.4178645-05:00",
+                "MessageID": "0a129aee-e1cd-480d-b08d-4f48548ff48d",
+                "ErrorCode": 0,
+                "Message": "OK"
+            }))),
+        );
+
+        let req = SendEmailRequest::builder()
+            .from("pa@example.com")
+            .to("mathieu@example.com")
+            .body(Body::text("hello matt".into()))
+            .build();
+
+        let resp = client(&server)
+            .execute_endpoint(req)
+            .await
+            .expect("json decode");
+        assert_eq!(resp.error_code, 0);
+    }
+
+    #[tokio::test]
+    async fn non_success_status_returns_api_error() {
+        let server = Server::run();
+
+        server.expect(
+            Expectation::matching(request::method_path("POST", "/email")).respond_with(
+                status_code(401).body(r#"{"ErrorCode":10,"Message":"Bad or missing API token"}"#),
+            ),
+        );
+
+        let req = SendEmailRequest::builder()
+            .from("pa@example.com")
+            .to("mathieu@example.com")
+            .body(Body::text("hello matt".into()))
+            .build();
+
+        let error = req.execute(&client(&server)).await.expect_err("api error");
+        assert!(matches!(
+            error,
+            QueryError::Api(error)
+                if error.error_code == Some(PostmarkErrorCode::BadOrMissingApiToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn request_timeout_bounds_the_exchange() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/templates"))
+                .times(2)
+                .respond_with(delay_and_then(
+                    std::time::Duration::from_millis(200),
+                    status_code(200),
+                )),
+        );
+        let client = client(&server);
+        let request = |timeout| {
+            Request::get("/templates")
+                .extension(RequestTimeout(std::time::Duration::from_millis(timeout)))
+                .body(Bytes::new())
+                .expect("request")
+        };
+
+        let error = client.execute(request(20)).await.expect_err("timeout");
+        assert!(matches!(error, HyperPostmarkClientError::Timeout { .. }));
+        client.execute(request(5_000)).await.expect("in time");
+    }
+
+    #[test]
+    fn debug_hides_tokens() {
+        let client = HyperPostmarkClient::builder()
+            .server_token("secret")
+            .client(
+                hyper_util::client::legacy::Client::builder(TokioExecutor::new())
+                    .build(HttpConnector::new()),
+            )
+            .build();
+
+        assert!(!format!("{client:?}").contains("secret"));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[tokio::test]
+    async fn decodes_gzip_responses() {
+        use std::io::Write;
+
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(br#"{"TotalCount":2,"Templates":[]}"#)
+            .expect("compress");
+        let body = encoder.finish().expect("compress");
+
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/templates"),
+                request::headers(contains(("accept-encoding", "gzip"))),
+            ])
+            .respond_with(
+                status_code(200)
+                    .insert_header("Content-Encoding", "gzip")
+                    .body(body),
+            ),
+        );
+
+        let resp = client(&server)
+            .execute_endpoint(crate::api::templates::ListTemplatesRequest::builder().build())
+            .await
+            .expect("decoded templates");
+        assert_eq!(resp.total_count, 2);
+    }
+}
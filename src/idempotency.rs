@@ -0,0 +1,850 @@
+//! A [`Client`] decorator that keeps retried sends from emailing twice.
+//!
+//! Postmark has no idempotency key: a send retried after a timeout may be
+//! delivered twice. [`IdempotentClient`] remembers the response of every
+//! accepted message for a while, in an [`IdempotencyStore`], and answers a
+//! repeated send with it instead of posting it again.
+//!
+//! Messages are identified by an [`IdempotencyKey`] given with
+//! [`IdempotentClient::with_key`]; sends without one are posted as usual.
+//! [`IdempotentClient::with_content_hashing`] identifies them by a hash of
+//! their JSON instead, at the cost of dropping legitimate repeats of the same
+//! email within the TTL. Batches are deduplicated message by message: only
+//! the messages not sent yet are posted, and the response lists every
+//! message in its original order.
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! # use postmark::api::{Body, email::SendEmailRequest};
+//! use postmark::Query;
+//! use postmark::idempotency::IdempotentClient;
+//! use std::time::Duration;
+//!
+//! # async fn f(email: SendEmailRequest) -> Result<(), Box<dyn std::error::Error>> {
+//! let client = IdempotentClient::new(PostmarkClient::default())
+//!     .with_ttl(Duration::from_secs(3600));
+//!
+//! email.execute(&client.with_key("order-1234-confirmation")).await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Only `/email`, `/email/withTemplate`, `/email/batch` and
+//! `/email/batchWithTemplates` are deduplicated. Two identical sends racing
+//! each other are both posted, as neither has a response yet.
+//!
+//! A response the store fails to remember is still returned, since its
+//! messages went out. The failure is added to its extensions as an
+//! [`UnstoredResponses`], and logged with the `tracing` feature.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::header::CONTENT_LENGTH;
+use http::{Method, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::{Client, EndpointInfo, ServerTokenOverride};
+
+/// How long responses are remembered by default.
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A caller provided key identifying a send. Messages of a batch get the key
+/// followed by `:` and their index.
+///
+/// [`KeyedClient`] attaches it to the extensions of every request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IdempotencyKey(pub String);
+
+/// Where an [`IdempotentClient`] keeps the responses of accepted messages.
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync {
+    /// The response stored under `key`, unless it expired.
+    async fn get(&self, key: &str) -> io::Result<Option<String>>;
+    /// Store `response`, a JSON `SendEmailResponse`, under `key` for `ttl`.
+    async fn put(&self, key: &str, response: String, ttl: Duration) -> io::Result<()>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Entry {
+    response: String,
+    /// Seconds since the Unix epoch.
+    expires_at: u64,
+}
+
+impl Entry {
+    fn new(response: String, ttl: Duration) -> Self {
+        Self {
+            response,
+            expires_at: unix_now().saturating_add(ttl.as_secs()),
+        }
+    }
+
+    fn is_live(&self, now: u64) -> bool {
+        self.expires_at > now
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// An [`IdempotencyStore`] in memory, lost when the process exits.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().expect("idempotency store poisoned")
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryStore {
+    async fn get(&self, key: &str) -> io::Result<Option<String>> {
+        let now = unix_now();
+        Ok(self
+            .lock()
+            .get(key)
+            .filter(|entry| entry.is_live(now))
+            .map(|entry| entry.response.clone()))
+    }
+
+    async fn put(&self, key: &str, response: String, ttl: Duration) -> io::Result<()> {
+        let now = unix_now();
+        let mut entries = self.lock();
+        entries.retain(|_, entry| entry.is_live(now));
+        entries.insert(key.to_string(), Entry::new(response, ttl));
+        Ok(())
+    }
+}
+
+/// An [`IdempotencyStore`] in a JSON file, kept across restarts.
+///
+/// The whole file is rewritten on every stored response, on Tokio's
+/// blocking threads, so it suits one process sending at a moderate rate.
+#[derive(Debug)]
+pub struct FileStore {
+    path: PathBuf,
+    entries: Mutex<HashMap<String, Entry>>,
+    /// Held while the file is written, so writes land in order.
+    writing: tokio::sync::Mutex<()>,
+}
+
+impl FileStore {
+    /// Open the store at `path`, which is created on the first stored
+    /// response when it does not exist.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let entries = match std::fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error),
+        };
+
+        Ok(Self {
+            path,
+            entries: Mutex::new(entries),
+            writing: tokio::sync::Mutex::new(()),
+        })
+    }
+
+    pub fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().expect("idempotency store poisoned")
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for FileStore {
+    async fn get(&self, key: &str) -> io::Result<Option<String>> {
+        let now = unix_now();
+        Ok(self
+            .lock()
+            .get(key)
+            .filter(|entry| entry.is_live(now))
+            .map(|entry| entry.response.clone()))
+    }
+
+    async fn put(&self, key: &str, response: String, ttl: Duration) -> io::Result<()> {
+        let now = unix_now();
+        {
+            let mut entries = self.lock();
+            entries.retain(|_, entry| entry.is_live(now));
+            entries.insert(key.to_string(), Entry::new(response, ttl));
+        }
+
+        // The entries are read once the previous write is over, so the last
+        // write always holds every stored response.
+        let _writing = self.writing.lock().await;
+        let content = serde_json::to_vec(&*self.lock())?;
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // Write then rename, so a crash never leaves a truncated file.
+            let tmp = path.with_extension("tmp");
+            std::fs::write(&tmp, content)?;
+            std::fs::rename(tmp, &path)
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+}
+
+/// Added to the extensions of a response whose messages were sent but not
+/// all remembered by the [`IdempotencyStore`]. Sending them again posts
+/// them again.
+#[derive(Debug, Clone)]
+pub struct UnstoredResponses {
+    /// The keys the store failed to remember.
+    pub keys: Vec<String>,
+    /// The last error of the store.
+    pub source: Arc<io::Error>,
+}
+
+#[derive(Error, Debug)]
+pub enum IdempotencyError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// The wrapped client failed.
+    #[error("{}", source)]
+    Inner { source: E },
+    /// The [`IdempotencyStore`] failed.
+    #[error("idempotency store error: {}", source)]
+    Store { source: io::Error },
+}
+
+/// Wraps a [`Client`] and answers repeated sends from an
+/// [`IdempotencyStore`], see the [module documentation](self).
+#[derive(Debug)]
+pub struct IdempotentClient<C, S = MemoryStore> {
+    inner: C,
+    store: S,
+    ttl: Duration,
+    hash_content: bool,
+}
+
+impl<C> IdempotentClient<C> {
+    /// Remember responses in a [`MemoryStore`] for [`DEFAULT_TTL`].
+    pub fn new(inner: C) -> Self {
+        Self::with_store(inner, MemoryStore::new())
+    }
+}
+
+impl<C, S> IdempotentClient<C, S> {
+    /// Remember responses in `store` for [`DEFAULT_TTL`].
+    pub fn with_store(inner: C, store: S) -> Self {
+        Self {
+            inner,
+            store,
+            ttl: DEFAULT_TTL,
+            hash_content: false,
+        }
+    }
+
+    /// Remember responses for `ttl`.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Identify sends without an [`IdempotencyKey`] by a hash of their
+    /// content and server token.
+    ///
+    /// An identical email sent again on purpose within the TTL, such as a
+    /// second password reset, is then answered from the store and never
+    /// delivered.
+    pub fn with_content_hashing(mut self) -> Self {
+        self.hash_content = true;
+        self
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Borrow this client to identify sends by `key` rather than by their
+    /// content.
+    pub fn with_key(&self, key: impl Into<String>) -> KeyedClient<'_, Self> {
+        KeyedClient {
+            inner: self,
+            key: IdempotencyKey(key.into()),
+        }
+    }
+}
+
+/// Where the messages of a send request are in its JSON body.
+#[derive(Clone, Copy)]
+enum Shape {
+    /// The body is the message.
+    Single,
+    /// The body is an array of messages.
+    Batch,
+    /// The messages are in the `Messages` field.
+    Messages,
+}
+
+impl Shape {
+    fn of(path: &str) -> Option<Self> {
+        match path {
+            "/email" | "/email/withTemplate" => Some(Self::Single),
+            "/email/batch" => Some(Self::Batch),
+            "/email/batchWithTemplates" => Some(Self::Messages),
+            _ => None,
+        }
+    }
+
+    fn messages(self, body: Value) -> Option<Vec<Value>> {
+        match (self, body) {
+            (Self::Single, message) => Some(vec![message]),
+            (Self::Batch, Value::Array(messages)) => Some(messages),
+            (Self::Messages, Value::Object(mut body)) => match body.remove("Messages") {
+                Some(Value::Array(messages)) => Some(messages),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn body(self, mut messages: Vec<Value>) -> Value {
+        match self {
+            Self::Single => messages.pop().unwrap_or_default(),
+            Self::Batch => Value::Array(messages),
+            Self::Messages => serde_json::json!({ "Messages": messages }),
+        }
+    }
+
+    fn responses(self, body: &[u8]) -> Option<Vec<Value>> {
+        match (self, serde_json::from_slice(body).ok()?) {
+            (Self::Single, response) => Some(vec![response]),
+            (_, Value::Array(responses)) => Some(responses),
+            _ => None,
+        }
+    }
+}
+
+impl<C, S> IdempotentClient<C, S>
+where
+    C: Client + Send + Sync,
+    S: IdempotencyStore,
+{
+    /// The keys of `messages`, or `None` when they are not deduplicated.
+    fn keys(&self, req: &Request<Bytes>, shape: Shape, messages: &[Value]) -> Option<Vec<String>> {
+        if let Some(IdempotencyKey(key)) = req.extensions().get::<IdempotencyKey>() {
+            return Some(match shape {
+                Shape::Single => vec![key.clone()],
+                Shape::Batch | Shape::Messages => {
+                    (0..messages.len()).map(|i| format!("{key}:{i}")).collect()
+                }
+            });
+        }
+        if !self.hash_content {
+            return None;
+        }
+
+        // Identical messages sent for different servers are different sends.
+        let token = req
+            .extensions()
+            .get::<ServerTokenOverride>()
+            .map(|token| token.0.as_str())
+            .unwrap_or_default();
+        let keys = messages
+            .iter()
+            .map(|message| {
+                let mut canonical = String::new();
+                write_canonical(message, &mut canonical);
+
+                let mut hasher = Sha256::new();
+                hasher.update(token);
+                hasher.update([0]);
+                hasher.update(canonical);
+                hasher
+                    .finalize()
+                    .iter()
+                    .fold(String::with_capacity(64), |mut hex, byte| {
+                        let _ = write!(hex, "{byte:02x}");
+                        hex
+                    })
+            })
+            .collect();
+        Some(keys)
+    }
+
+    async fn send(
+        &self,
+        req: Request<Bytes>,
+        shape: Shape,
+    ) -> Result<Response<Bytes>, IdempotencyError<C::Error>> {
+        let Some(messages) = serde_json::from_slice(req.body())
+            .ok()
+            .and_then(|body| shape.messages(body))
+        else {
+            return self.forward(req).await;
+        };
+        let Some(keys) = self.keys(&req, shape, &messages) else {
+            return self.forward(req).await;
+        };
+
+        let mut stored = Vec::with_capacity(keys.len());
+        for key in &keys {
+            let response = self
+                .store
+                .get(key)
+                .await
+                .map_err(|source| IdempotencyError::Store { source })?;
+            stored.push(response.and_then(|response| serde_json::from_str(&response).ok()));
+        }
+
+        let missing: Vec<usize> = (0..messages.len())
+            .filter(|&i| stored[i].is_none())
+            .collect();
+        if missing.is_empty() {
+            let responses = stored.into_iter().flatten().collect();
+            return Ok(json_response(
+                StatusCode::OK,
+                &shape_responses(shape, responses),
+            ));
+        }
+
+        let all_missing = missing.len() == messages.len();
+        let (mut parts, mut body) = req.into_parts();
+        if !all_missing {
+            let unsent = messages
+                .into_iter()
+                .enumerate()
+                .filter(|(i, _)| stored[*i].is_none())
+                .map(|(_, message)| message)
+                .collect();
+            parts.headers.remove(CONTENT_LENGTH);
+            body = serde_json::to_vec(&shape.body(unsent))
+                .expect("messages serialize to JSON")
+                .into();
+        }
+
+        let mut rsp = self.forward(Request::from_parts(parts, body)).await?;
+        let fresh = match (rsp.status(), shape.responses(rsp.body())) {
+            (StatusCode::OK, Some(fresh)) if fresh.len() == missing.len() => fresh,
+            // Nothing was sent, or the response cannot be matched to the
+            // messages: hand it over as is.
+            _ => return Ok(rsp),
+        };
+
+        // The messages were sent: a store failure must not hide it, nor
+        // keep the other responses from being stored.
+        let mut unstored = Vec::new();
+        let mut failure = None;
+        for (&i, response) in missing.iter().zip(&fresh) {
+            if response.get("ErrorCode").and_then(Value::as_i64) != Some(0) {
+                continue;
+            }
+            if let Err(source) = self
+                .store
+                .put(&keys[i], response.to_string(), self.ttl)
+                .await
+            {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(
+                    idempotency.key = %keys[i],
+                    error = %source,
+                    "postmark idempotency store failed to remember a sent message"
+                );
+                unstored.push(keys[i].clone());
+                failure = Some(source);
+            }
+        }
+        if let Some(source) = failure {
+            rsp.extensions_mut().insert(UnstoredResponses {
+                keys: unstored,
+                source: Arc::new(source),
+            });
+        }
+        if all_missing {
+            return Ok(rsp);
+        }
+
+        let mut fresh = fresh.into_iter();
+        let responses = stored
+            .into_iter()
+            .map(|response| response.or_else(|| fresh.next()).unwrap_or_default())
+            .collect();
+        let (mut parts, _) = rsp.into_parts();
+        parts.headers.remove(CONTENT_LENGTH);
+        let body = serde_json::to_vec(&shape_responses(shape, responses))
+            .expect("responses serialize to JSON");
+        Ok(Response::from_parts(parts, body.into()))
+    }
+
+    async fn forward(
+        &self,
+        req: Request<Bytes>,
+    ) -> Result<Response<Bytes>, IdempotencyError<C::Error>> {
+        self.inner
+            .execute(req)
+            .await
+            .map_err(|source| IdempotencyError::Inner { source })
+    }
+}
+
+fn shape_responses(shape: Shape, mut responses: Vec<Value>) -> Value {
+    match shape {
+        Shape::Single => responses.pop().unwrap_or_default(),
+        Shape::Batch | Shape::Messages => Value::Array(responses),
+    }
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response<Bytes> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(body.to_string().into())
+        .expect("stored response is a valid http response")
+}
+
+/// Serialize `value` with object keys sorted, so equal messages hash the
+/// same whatever the order of their fields.
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(object) => {
+            let mut fields: Vec<_> = object.iter().collect();
+            fields.sort_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (i, (key, value)) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        Value::Array(values) => {
+            out.push('[');
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(value, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[async_trait]
+impl<C, S> Client for IdempotentClient<C, S>
+where
+    C: Client + Send + Sync,
+    S: IdempotencyStore,
+{
+    type Error = IdempotencyError<C::Error>;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let path = match req.extensions().get::<EndpointInfo>() {
+            Some(info) => info.path_template.to_string(),
+            None => req.uri().path().to_string(),
+        };
+
+        match Shape::of(&path) {
+            Some(shape) if req.method() == Method::POST => self.send(req, shape).await,
+            _ => self.forward(req).await,
+        }
+    }
+}
+
+/// Borrows a [`Client`] and sends every request with an
+/// [`IdempotencyKey`], see [`IdempotentClient::with_key`].
+#[derive(Debug, Clone)]
+pub struct KeyedClient<'a, C> {
+    inner: &'a C,
+    key: IdempotencyKey,
+}
+
+impl<'a, C> KeyedClient<'a, C> {
+    /// The wrapped client.
+    pub fn inner(&self) -> &'a C {
+        self.inner
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key.0
+    }
+}
+
+#[async_trait]
+impl<C> Client for KeyedClient<'_, C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = C::Error;
+
+    async fn execute(&self, mut req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        req.extensions_mut().insert(self.key.clone());
+        self.inner.execute(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::api::Body;
+    use crate::api::email::{SendEmailBatchRequest, SendEmailRequest, SendEmailResponse};
+    use crate::testing::{MockClient, MockResponse};
+    use crate::{PostmarkErrorCode, Query};
+
+    fn email(to: &str) -> SendEmailRequest {
+        SendEmailRequest::builder()
+            .from("me@example.com")
+            .to(to)
+            .body(Body::text("Hi".into()))
+            .build()
+    }
+
+    fn accepted(to: &str, id: &str) -> SendEmailResponse {
+        SendEmailResponse {
+            to: Some(to.into()),
+            message_id: Some(id.into()),
+            message: "OK".into(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn repeated_sends_are_answered_from_the_store() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email",
+            MockResponse::ok(accepted("a@example.com", "id-1")),
+        );
+        let client = IdempotentClient::new(mock.clone()).with_content_hashing();
+
+        for _ in 0..2 {
+            let rsp = email("a@example.com").execute(&client).await.expect("sent");
+            assert_eq!(rsp.message_id.as_deref(), Some("id-1"));
+        }
+        assert_eq!(mock.requests().len(), 1);
+
+        // Another message, or the same one under another key, is sent.
+        email("b@example.com").execute(&client).await.expect("sent");
+        email("a@example.com")
+            .execute(&client.with_key("resend"))
+            .await
+            .expect("sent");
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn only_keyed_sends_are_deduplicated_by_default() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email",
+            MockResponse::ok(accepted("a@example.com", "id-1")),
+        );
+        let client = IdempotentClient::new(mock.clone());
+
+        for _ in 0..2 {
+            email("a@example.com").execute(&client).await.expect("sent");
+            email("a@example.com")
+                .execute(&client.with_key("order-1"))
+                .await
+                .expect("sent");
+        }
+        assert_eq!(mock.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn failed_sends_are_not_remembered() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email",
+            MockResponse::inactive_recipient("a@example.com"),
+        );
+        let client = IdempotentClient::new(mock.clone()).with_content_hashing();
+
+        for _ in 0..2 {
+            email("a@example.com")
+                .execute(&client)
+                .await
+                .expect_err("inactive");
+        }
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn batches_only_send_the_messages_not_sent_yet() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::ok(vec![
+                accepted("a@example.com", "id-a"),
+                SendEmailResponse {
+                    error_code: PostmarkErrorCode::InactiveRecipient,
+                    message: "Inactive".into(),
+                    ..Default::default()
+                },
+            ]),
+        )
+        .respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::ok(vec![
+                accepted("b@example.com", "id-b"),
+                accepted("c@example.com", "id-c"),
+            ]),
+        );
+        let client = IdempotentClient::new(mock.clone()).with_content_hashing();
+
+        let first: SendEmailBatchRequest = vec![email("a@example.com"), email("b@example.com")];
+        first.execute(&client).await.expect("batch");
+
+        let second: SendEmailBatchRequest = vec![
+            email("a@example.com"),
+            email("b@example.com"),
+            email("c@example.com"),
+        ];
+        let rsp = second.execute(&client).await.expect("batch");
+        let ids: Vec<_> = rsp.iter().map(|r| r.message_id.as_deref()).collect();
+        assert_eq!(ids, [Some("id-a"), Some("id-b"), Some("id-c")]);
+
+        let sent: Vec<SendEmailBatchRequest> = mock.sent(Method::POST, "/email/batch");
+        assert_eq!(
+            sent[1],
+            vec![email("b@example.com"), email("c@example.com")]
+        );
+    }
+
+    /// Remembers nothing, and fails every `put`.
+    #[derive(Default)]
+    struct FailingStore {
+        puts: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl IdempotencyStore for FailingStore {
+        async fn get(&self, _key: &str) -> io::Result<Option<String>> {
+            Ok(None)
+        }
+
+        async fn put(&self, _key: &str, _response: String, _ttl: Duration) -> io::Result<()> {
+            self.puts.fetch_add(1, Ordering::SeqCst);
+            Err(io::Error::other("disk full"))
+        }
+    }
+
+    #[tokio::test]
+    async fn sent_messages_are_returned_when_the_store_fails() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::ok(vec![
+                accepted("a@example.com", "id-a"),
+                accepted("b@example.com", "id-b"),
+            ]),
+        );
+        let client = IdempotentClient::with_store(mock.clone(), FailingStore::default());
+
+        let batch: SendEmailBatchRequest = vec![email("a@example.com"), email("b@example.com")];
+        let req = Request::post("/email/batch")
+            .body(Bytes::from(serde_json::to_vec(&batch).unwrap()))
+            .unwrap();
+        let rsp = client.with_key("order-1").execute(req).await.expect("sent");
+
+        assert_eq!(rsp.status(), StatusCode::OK);
+        let unstored = rsp
+            .extensions()
+            .get::<UnstoredResponses>()
+            .expect("store failure");
+        assert_eq!(unstored.keys, ["order-1:0", "order-1:1"]);
+        assert_eq!(client.store().puts.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn canonical_json_ignores_field_order() {
+        let mut a = String::new();
+        write_canonical(
+            &serde_json::json!({ "b": 1, "a": { "d": [1], "c": "x" } }),
+            &mut a,
+        );
+        let mut b = String::new();
+        write_canonical(
+            &serde_json::json!({ "a": { "c": "x", "d": [1] }, "b": 1 }),
+            &mut b,
+        );
+        assert_eq!(a, b);
+        assert_eq!(a, r#"{"a":{"c":"x","d":[1]},"b":1}"#);
+    }
+
+    fn store_path() -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        std::env::temp_dir().join(format!(
+            "postmark-idempotency-{}-{}.json",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ))
+    }
+
+    #[tokio::test]
+    async fn file_store_keeps_live_entries_across_opens() {
+        let path = store_path();
+
+        let store = FileStore::open(&path).expect("open");
+        store
+            .put("live", "{}".into(), Duration::from_secs(60))
+            .await
+            .expect("put");
+        store
+            .put("expired", "{}".into(), Duration::ZERO)
+            .await
+            .expect("put");
+
+        let store = FileStore::open(&path).expect("reopen");
+        assert_eq!(store.get("live").await.expect("get").as_deref(), Some("{}"));
+        assert_eq!(store.get("expired").await.expect("get"), None);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,215 @@
+//! `tracing` instrumentation of [`Query::execute`](crate::Query::execute).
+//!
+//! Every executed [`Endpoint`] runs inside a `postmark.request` span that
+//! records the method, the endpoint path template, the response status, the
+//! latency and, when Postmark returns them, the `ErrorCode` and `MessageID`.
+//! Request headers are never recorded, so tokens do not leak into traces.
+
+use std::time::Instant;
+
+use bytes::Bytes;
+use http::{Request, Response};
+use tracing::Instrument;
+use tracing::field::Empty;
+
+use crate::{Client, Endpoint};
+
+pub(crate) async fn execute<T, C>(
+    endpoint: &T,
+    client: &C,
+    req: Request<Bytes>,
+) -> Result<Response<Bytes>, C::Error>
+where
+    T: Endpoint,
+    C: Client,
+{
+    let span = tracing::info_span!(
+        "postmark.request",
+        http.method = %endpoint.method(),
+        postmark.endpoint = %endpoint.path_template(),
+        http.status_code = Empty,
+        postmark.error_code = Empty,
+        postmark.message_id = Empty,
+        latency_ms = Empty,
+    );
+
+    let start = Instant::now();
+    let result = client.execute(req).instrument(span.clone()).await;
+    span.record("latency_ms", start.elapsed().as_millis() as u64);
+
+    match &result {
+        Ok(response) => {
+            span.record("http.status_code", response.status().as_u16());
+
+            if !span.is_disabled() {
+                #[derive(serde::Deserialize)]
+                struct Summary {
+                    #[serde(rename = "ErrorCode")]
+                    error_code: Option<i64>,
+                    #[serde(rename = "MessageID")]
+                    message_id: Option<String>,
+                }
+
+                if let Ok(summary) = serde_json::from_slice::<Summary>(response.body()) {
+                    if let Some(error_code) = summary.error_code {
+                        span.record("postmark.error_code", error_code);
+                    }
+                    if let Some(message_id) = summary.message_id {
+                        span.record("postmark.message_id", message_id);
+                    }
+                }
+            }
+        }
+        Err(err) => {
+            span.in_scope(|| tracing::warn!(error = %err, "postmark request failed"));
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+    use http::StatusCode;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use crate::Query;
+    use crate::api::Body;
+    use crate::api::email::SendEmailRequest;
+    use crate::api::templates::GetTemplateRequest;
+
+    use super::*;
+
+    /// Collects every field recorded on spans, ignoring span identity.
+    #[derive(Clone, Default)]
+    struct FieldRecorder {
+        fields: Arc<Mutex<HashMap<String, String>>>,
+    }
+
+    impl FieldRecorder {
+        fn get(&self, name: &str) -> Option<String> {
+            self.fields.lock().expect("lock").get(name).cloned()
+        }
+    }
+
+    impl Visit for FieldRecorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.fields
+                .lock()
+                .expect("lock")
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.fields
+                .lock()
+                .expect("lock")
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    impl Subscriber for FieldRecorder {
+        fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            span.record(&mut self.clone());
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _span: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            event.record(&mut self.clone());
+        }
+
+        fn enter(&self, _span: &Id) {}
+
+        fn exit(&self, _span: &Id) {}
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("test client error")]
+    struct TestClientError;
+
+    struct CannedClient {
+        status: StatusCode,
+        body: &'static str,
+    }
+
+    #[async_trait]
+    impl Client for CannedClient {
+        type Error = TestClientError;
+
+        async fn execute(&self, _req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+            Ok(Response::builder()
+                .status(self.status)
+                .body(Bytes::from_static(self.body.as_bytes()))
+                .expect("response"))
+        }
+    }
+
+    #[tokio::test]
+    async fn send_span_records_status_error_code_and_message_id() {
+        let recorder = FieldRecorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let client = CannedClient {
+            status: StatusCode::OK,
+            body: r#"{"MessageID":"0a129aee","ErrorCode":0,"Message":"OK"}"#,
+        };
+        SendEmailRequest::builder()
+            .from("pa@example.com")
+            .to("mathieu@example.com")
+            .body(Body::text("hello".into()))
+            .build()
+            .execute(&client)
+            .await
+            .expect("execute");
+
+        assert_eq!(recorder.get("http.method").as_deref(), Some("POST"));
+        assert_eq!(recorder.get("postmark.endpoint").as_deref(), Some("/email"));
+        assert_eq!(recorder.get("http.status_code").as_deref(), Some("200"));
+        assert_eq!(recorder.get("postmark.error_code").as_deref(), Some("0"));
+        assert_eq!(
+            recorder.get("postmark.message_id").as_deref(),
+            Some("0a129aee")
+        );
+        assert!(recorder.get("latency_ms").is_some());
+    }
+
+    #[tokio::test]
+    async fn span_uses_path_template_and_records_api_errors() {
+        let recorder = FieldRecorder::default();
+        let _guard = tracing::subscriber::set_default(recorder.clone());
+
+        let client = CannedClient {
+            status: StatusCode::UNPROCESSABLE_ENTITY,
+            body: r#"{"ErrorCode":1101,"Message":"Template not found"}"#,
+        };
+        GetTemplateRequest::builder()
+            .id(1234)
+            .build()
+            .execute(&client)
+            .await
+            .expect_err("api error");
+
+        assert_eq!(
+            recorder.get("postmark.endpoint").as_deref(),
+            Some("/templates/{id}")
+        );
+        assert_eq!(recorder.get("http.status_code").as_deref(), Some("422"));
+        assert_eq!(recorder.get("postmark.error_code").as_deref(), Some("1101"));
+    }
+}
@@ -9,6 +9,7 @@
 //! work transparently with this library.
 //!
 //! To use the [`reqwest`] based client, enable the `"reqwest"` feature.
+//! To use the [`hyper`] based client, enable the `"hyper"` feature.
 //! You can also implement your own client by implementing the [`Client`] trait.
 //!
 //! This crate is heavily inspired by the article ["Designing Rust bindings for REST APIs](https://plume.benboeckel.net/~/JustAnotherBlog/designing-rust-bindings-for-rest-ap-is)
@@ -42,8 +43,55 @@ pub const POSTMARK_API_URL: &str = "https://api.postmarkapp.com/";
 
 pub mod api;
 mod client;
+mod error_code;
+#[cfg(feature = "tracing")]
+mod instrument;
+mod meta;
+mod raw;
+#[cfg(any(feature = "reqwest", feature = "hyper"))]
+mod transport;
 
 pub use client::*;
+pub use error_code::*;
+pub use meta::*;
+pub use raw::*;
 
 #[cfg(feature = "reqwest")]
 pub mod reqwest;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+pub mod batch;
+
+pub mod circuit_breaker;
+
+#[cfg(feature = "dry-run")]
+pub mod dry_run;
+
+#[cfg(feature = "gzip")]
+pub mod gzip;
+
+#[cfg(feature = "hyper")]
+pub mod hyper;
+
+#[cfg(feature = "idempotency")]
+pub mod idempotency;
+
+pub mod metrics;
+
+pub mod paginate;
+
+pub mod pool;
+
+#[cfg(feature = "rate-limit")]
+pub mod rate_limit;
+
+#[cfg(feature = "retry")]
+pub mod retry;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "tower")]
+pub mod tower;
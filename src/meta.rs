@@ -0,0 +1,184 @@
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use http::{HeaderMap, StatusCode};
+
+use crate::client::{parse_response, send};
+use crate::{AccountClient, AccountToken, Client, Endpoint, Query, QueryError};
+use crate::{ServerClient, ServerToken};
+
+/// Wraps an [`Endpoint`] so that executing it returns a [`MetaResponse`],
+/// the typed body along with what the HTTP response said about it.
+///
+/// ```
+/// # use postmark::reqwest::PostmarkClient;
+/// # use postmark::api::templates::ListTemplatesRequest;
+/// use postmark::{Query, WithMeta};
+///
+/// # async fn f(client: &PostmarkClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let rsp = WithMeta(ListTemplatesRequest::builder().build())
+///     .execute(client)
+///     .await?;
+/// println!("{} in {:?}: {:?}", rsp.status, rsp.elapsed, rsp.headers);
+/// let templates = rsp.body;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Failed requests keep their headers in [`QueryError::Api`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithMeta<E>(pub E);
+
+/// The response of an endpoint executed through [`WithMeta`].
+#[derive(Debug, Clone)]
+pub struct MetaResponse<T> {
+    /// HTTP status code.
+    pub status: StatusCode,
+    /// Response headers.
+    pub headers: HeaderMap,
+    /// Time from handing the request to the client to getting its response,
+    /// including the work of any client decorators such as retries.
+    pub elapsed: Duration,
+    /// The parsed response body.
+    pub body: T,
+}
+
+impl<T> MetaResponse<T> {
+    pub fn into_body(self) -> T {
+        self.body
+    }
+}
+
+impl<E> WithMeta<E>
+where
+    E: Endpoint,
+{
+    fn parse<Err>(
+        &self,
+        response: http::Response<bytes::Bytes>,
+        elapsed: Duration,
+    ) -> Result<MetaResponse<E::Response>, QueryError<Err>>
+    where
+        Err: std::error::Error + Send + Sync + 'static,
+    {
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = parse_response(&self.0, response)?;
+
+        Ok(MetaResponse {
+            status,
+            headers,
+            elapsed,
+            body,
+        })
+    }
+}
+
+#[async_trait]
+impl<E, C> Query<C> for WithMeta<E>
+where
+    E: Endpoint + Send + Sync,
+    C: Client + Send + Sync,
+{
+    type Result = Result<MetaResponse<E::Response>, QueryError<C::Error>>;
+
+    async fn execute(self, client: &C) -> Self::Result {
+        let start = Instant::now();
+        let response = send(&self.0, client).await?;
+        self.parse(response, start.elapsed())
+    }
+}
+
+#[async_trait]
+impl<E, C> Query<ServerClient<C>> for WithMeta<E>
+where
+    E: Endpoint<Scope = ServerToken> + Send + Sync,
+    C: Client + Send + Sync,
+{
+    type Result = Result<MetaResponse<E::Response>, QueryError<C::Error>>;
+
+    async fn execute(self, client: &ServerClient<C>) -> Self::Result {
+        Query::<C>::execute(self, client.inner()).await
+    }
+}
+
+#[async_trait]
+impl<E, C> Query<AccountClient<C>> for WithMeta<E>
+where
+    E: Endpoint<Scope = AccountToken> + Send + Sync,
+    C: Client + Send + Sync,
+{
+    type Result = Result<MetaResponse<E::Response>, QueryError<C::Error>>;
+
+    async fn execute(self, client: &AccountClient<C>) -> Self::Result {
+        Query::<C>::execute(self, client.inner()).await
+    }
+}
+
+#[cfg(feature = "blocking")]
+impl<E, C> crate::blocking::BlockingQuery<C> for WithMeta<E>
+where
+    E: Endpoint,
+    C: crate::blocking::Client,
+{
+    type Result = Result<MetaResponse<E::Response>, QueryError<C::Error>>;
+
+    fn execute_blocking(self, client: &C) -> Self::Result {
+        let http_req = crate::client::build_request(&self.0)?;
+        let start = Instant::now();
+        let response = client.execute(http_req).map_err(QueryError::client)?;
+        self.parse(response, start.elapsed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+
+    use super::*;
+    use crate::api::templates::{ListTemplatesRequest, ListTemplatesResponse};
+    use crate::testing::{MockClient, MockResponse};
+
+    #[tokio::test]
+    async fn returns_status_and_headers_with_the_body() {
+        let client = MockClient::new();
+        client.respond(
+            Method::GET,
+            "/templates",
+            MockResponse::ok(ListTemplatesResponse {
+                total_count: 3,
+                templates: vec![],
+            })
+            .header("x-request-id", "abc"),
+        );
+
+        let rsp = WithMeta(ListTemplatesRequest::builder().build())
+            .execute(&client)
+            .await
+            .expect("templates");
+
+        assert_eq!(rsp.status, StatusCode::OK);
+        assert_eq!(rsp.headers["x-request-id"], "abc");
+        assert_eq!(rsp.body.total_count, 3);
+    }
+
+    #[tokio::test]
+    async fn api_errors_keep_the_response_headers() {
+        let client = MockClient::new();
+        client.respond(
+            Method::GET,
+            "/templates",
+            MockResponse::server_error().header("x-request-id", "abc"),
+        );
+
+        let error = WithMeta(ListTemplatesRequest::builder().build())
+            .execute(&ServerClient::new(client))
+            .await
+            .expect_err("server error");
+
+        match error {
+            QueryError::Api(error) => assert_eq!(error.headers["x-request-id"], "abc"),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,347 @@
+//! Request metrics for every executed [`Endpoint`](crate::Endpoint).
+//!
+//! Wrap a client in an [`ObservedClient`] and every request issued through
+//! [`Query::execute`](crate::Query::execute) is reported to a
+//! [`MetricsObserver`] once it completes. With the `metrics` feature enabled,
+//! [`RecorderObserver`] forwards them to the
+//! [`metrics`](https://docs.rs/metrics) crate's global recorder.
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use postmark::metrics::{MetricsObserver, ObservedClient, RequestObservation};
+//!
+//! struct PrintObserver;
+//!
+//! impl MetricsObserver for PrintObserver {
+//!     fn observe(&self, observation: &RequestObservation<'_>) {
+//!         println!("{} {} took {:?}", observation.method, observation.endpoint, observation.duration);
+//!     }
+//! }
+//!
+//! let client = ObservedClient::new(PostmarkClient::default(), PrintObserver);
+//! ```
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Method, Request, Response, StatusCode};
+
+use crate::{Client, EndpointInfo, PostmarkErrorCode};
+
+/// What is known about a request once it completed.
+#[derive(Debug, Clone)]
+pub struct RequestObservation<'a> {
+    /// The endpoint path template, such as `/templates/{id}`.
+    ///
+    /// Falls back to the request path when the request was not built by
+    /// [`Query`](crate::Query).
+    pub endpoint: &'a str,
+    /// The HTTP method of the request.
+    pub method: &'a Method,
+    /// The response status, or `None` when the client failed.
+    pub status: Option<StatusCode>,
+    /// The Postmark `ErrorCode` of the response body, when present.
+    pub error_code: Option<PostmarkErrorCode>,
+    /// The message stream the request sends to, see
+    /// [`Endpoint::message_stream`](crate::Endpoint::message_stream). Sends
+    /// naming no stream report `outbound`; batches mixing several streams
+    /// and requests that do not send have none.
+    pub message_stream: Option<&'a str>,
+    /// Time spent in the wrapped client.
+    pub duration: Duration,
+    /// Size of the request body in bytes.
+    pub request_body_size: usize,
+}
+
+/// Receives a [`RequestObservation`] after each request of an
+/// [`ObservedClient`].
+pub trait MetricsObserver: Send + Sync {
+    fn observe(&self, observation: &RequestObservation<'_>);
+}
+
+/// Wraps a [`Client`] and reports every request to a [`MetricsObserver`].
+#[derive(Debug, Clone)]
+pub struct ObservedClient<C, O> {
+    inner: C,
+    observer: O,
+}
+
+impl<C, O> ObservedClient<C, O> {
+    pub fn new(inner: C, observer: O) -> Self {
+        Self { inner, observer }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The observer requests are reported to.
+    pub fn observer(&self) -> &O {
+        &self.observer
+    }
+}
+
+#[async_trait]
+impl<C, O> Client for ObservedClient<C, O>
+where
+    C: Client + Send + Sync,
+    O: MetricsObserver,
+{
+    type Error = C::Error;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let method = req.method().clone();
+        let info = req.extensions().get::<EndpointInfo>();
+        let endpoint = match info {
+            Some(info) => info.path_template.to_string(),
+            None => req.uri().path().to_string(),
+        };
+        let message_stream = info.and_then(|info| info.message_stream.clone());
+        let request_body_size = req.body().len();
+
+        let start = Instant::now();
+        let result = self.inner.execute(req).await;
+        let duration = start.elapsed();
+
+        let (status, error_code) = match &result {
+            Ok(response) => (Some(response.status()), error_code(response.body())),
+            Err(_) => (None, None),
+        };
+
+        self.observer.observe(&RequestObservation {
+            endpoint: &endpoint,
+            method: &method,
+            status,
+            error_code,
+            message_stream: message_stream.as_deref(),
+            duration,
+            request_body_size,
+        });
+
+        result
+    }
+}
+
+fn error_code(body: &[u8]) -> Option<PostmarkErrorCode> {
+    #[derive(serde::Deserialize)]
+    struct ErrorCode {
+        #[serde(rename = "ErrorCode")]
+        error_code: Option<PostmarkErrorCode>,
+    }
+
+    serde_json::from_slice::<ErrorCode>(body)
+        .ok()
+        .and_then(|body| body.error_code)
+}
+
+/// A [`MetricsObserver`] recording to the global
+/// [`metrics`](https://docs.rs/metrics) recorder.
+///
+/// Records:
+/// - `postmark_requests_total`, a counter labeled with `endpoint`, `method`,
+///   `status`, `error_code` and `message_stream`,
+/// - `postmark_request_duration_seconds`, a histogram labeled with
+///   `endpoint` and `method`,
+/// - `postmark_request_body_bytes`, a histogram labeled with `endpoint`.
+///
+/// Failed client calls are counted with a `status` of `error`. Missing error
+/// codes and message streams are recorded as an empty label.
+#[cfg(feature = "metrics")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RecorderObserver;
+
+#[cfg(feature = "metrics")]
+impl MetricsObserver for RecorderObserver {
+    fn observe(&self, observation: &RequestObservation<'_>) {
+        let endpoint = observation.endpoint.to_string();
+        let method = observation.method.to_string();
+        let status = observation
+            .status
+            .map(|status| status.as_u16().to_string())
+            .unwrap_or_else(|| "error".to_string());
+        let error_code = observation
+            .error_code
+            .map(|code| code.code().to_string())
+            .unwrap_or_default();
+        let message_stream = observation.message_stream.unwrap_or_default().to_string();
+
+        ::metrics::counter!(
+            "postmark_requests_total",
+            "endpoint" => endpoint.clone(),
+            "method" => method.clone(),
+            "status" => status,
+            "error_code" => error_code,
+            "message_stream" => message_stream,
+        )
+        .increment(1);
+        ::metrics::histogram!(
+            "postmark_request_duration_seconds",
+            "endpoint" => endpoint.clone(),
+            "method" => method,
+        )
+        .record(observation.duration.as_secs_f64());
+        ::metrics::histogram!("postmark_request_body_bytes", "endpoint" => endpoint)
+            .record(observation.request_body_size as f64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    use crate::api::Body;
+    use crate::api::email::{SendEmailBatchRequest, SendEmailRequest};
+    use crate::api::templates::GetTemplateRequest;
+    use crate::{Query, QueryError};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("test client error")]
+    struct TestClientError;
+
+    struct CannedClient {
+        response: Option<(StatusCode, &'static str)>,
+    }
+
+    #[async_trait]
+    impl Client for CannedClient {
+        type Error = TestClientError;
+
+        async fn execute(&self, _req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+            let (status, body) = self.response.ok_or(TestClientError)?;
+            Ok(Response::builder()
+                .status(status)
+                .body(Bytes::from_static(body.as_bytes()))
+                .expect("response"))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Owned {
+        endpoint: String,
+        method: Method,
+        status: Option<StatusCode>,
+        error_code: Option<PostmarkErrorCode>,
+        message_stream: Option<String>,
+        request_body_size: usize,
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        observations: Mutex<Vec<Owned>>,
+    }
+
+    impl MetricsObserver for Recorder {
+        fn observe(&self, observation: &RequestObservation<'_>) {
+            self.observations.lock().expect("lock").push(Owned {
+                endpoint: observation.endpoint.to_string(),
+                method: observation.method.clone(),
+                status: observation.status,
+                error_code: observation.error_code,
+                message_stream: observation.message_stream.map(str::to_string),
+                request_body_size: observation.request_body_size,
+            });
+        }
+    }
+
+    impl Recorder {
+        fn last(&self) -> Owned {
+            self.observations
+                .lock()
+                .expect("lock")
+                .last()
+                .cloned()
+                .expect("observation")
+        }
+    }
+
+    fn email() -> SendEmailRequest {
+        SendEmailRequest::builder()
+            .from("pa@example.com")
+            .to("mathieu@example.com")
+            .body(Body::text("hello".into()))
+            .message_stream("broadcast")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn observes_inactive_recipient_errors() {
+        let client = ObservedClient::new(
+            CannedClient {
+                response: Some((
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    r#"{"ErrorCode":406,"Message":"Inactive recipient"}"#,
+                )),
+            },
+            Recorder::default(),
+        );
+
+        let req = email();
+        let body_size = serde_json::to_vec(&req).expect("serialize").len();
+        req.execute(&client).await.expect_err("api error");
+
+        assert_eq!(
+            client.observer().last(),
+            Owned {
+                endpoint: "/email".into(),
+                method: Method::POST,
+                status: Some(StatusCode::UNPROCESSABLE_ENTITY),
+                error_code: Some(PostmarkErrorCode::InactiveRecipient),
+                message_stream: Some("broadcast".into()),
+                request_body_size: body_size,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn observes_batch_streams_and_path_templates() {
+        let client = ObservedClient::new(
+            CannedClient {
+                response: Some((StatusCode::OK, "[]")),
+            },
+            Recorder::default(),
+        );
+
+        let batch: SendEmailBatchRequest = vec![email(), email()];
+        batch.execute(&client).await.expect("execute");
+        let observation = client.observer().last();
+        assert_eq!(observation.endpoint, "/email/batch");
+        assert_eq!(observation.message_stream.as_deref(), Some("broadcast"));
+        assert_eq!(observation.error_code, None);
+
+        let mut outbound = email();
+        outbound.message_stream = None;
+        let batch: SendEmailBatchRequest = vec![outbound.clone()];
+        batch.execute(&client).await.expect("execute");
+        assert_eq!(
+            client.observer().last().message_stream.as_deref(),
+            Some("outbound")
+        );
+
+        let batch: SendEmailBatchRequest = vec![email(), outbound];
+        batch.execute(&client).await.expect("execute");
+        assert_eq!(client.observer().last().message_stream, None);
+
+        let _ = GetTemplateRequest::builder()
+            .id(42)
+            .build()
+            .execute(&client)
+            .await;
+        let observation = client.observer().last();
+        assert_eq!(observation.endpoint, "/templates/{id}");
+        assert_eq!(observation.method, Method::GET);
+        assert_eq!(observation.request_body_size, 0);
+        assert_eq!(observation.message_stream, None);
+    }
+
+    #[tokio::test]
+    async fn observes_client_failures() {
+        let client = ObservedClient::new(CannedClient { response: None }, Recorder::default());
+
+        let error = email().execute(&client).await.expect_err("client error");
+        assert!(matches!(error, QueryError::Client { .. }));
+        assert_eq!(client.observer().last().status, None);
+    }
+}
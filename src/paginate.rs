@@ -0,0 +1,919 @@
+//! Iterating every item of the endpoints paging with `count` and `offset`.
+//!
+//! Endpoints listing items page by page implement [`Paginated`]. With the
+//! `stream` feature, [`Paginated::paginate`] turns one into a [`Stream`] of
+//! items fetching pages lazily, as they are consumed:
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use futures_util::TryStreamExt;
+//! use postmark::api::bounce::ListBouncesRequest;
+//! use postmark::paginate::Paginated;
+//!
+//! # async fn f(client: &PostmarkClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let mut bounces = ListBouncesRequest::builder()
+//!     .build()
+//!     .paginate(client)
+//!     .page_size(500)
+//!     .prefetch(2);
+//!
+//! while let Some(bounce) = bounces.try_next().await? {
+//!     println!("{}", bounce.email);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Message searches and bounce lists only reach the first [`SEARCH_WINDOW`]
+//! items of a query, and their pagination stops there. Searches implementing [`TimeSliced`]
+//! can go past it with
+//! [`TimeSliced::search_all`], which splits a date range until every slice
+//! fits in the window:
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use futures_util::TryStreamExt;
+//! use postmark::api::messages::OutboundSearchRequest;
+//! use postmark::paginate::TimeSliced;
+//! use time::macros::datetime;
+//!
+//! # async fn f(client: &PostmarkClient) -> Result<(), Box<dyn std::error::Error>> {
+//! let messages: Vec<_> = OutboundSearchRequest::default()
+//!     .search_all(client, datetime!(2024-01-01 0:00), datetime!(2024-01-31 23:59:59))
+//!     .page_size(500)
+//!     .try_collect()
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Stream`]: futures_core::Stream
+
+use std::borrow::Cow;
+
+use crate::Endpoint;
+
+/// Postmark answers searches and bounce lists for up to this many items,
+/// counting the offset.
+pub const SEARCH_WINDOW: i64 = 10_000;
+
+/// The most items Postmark returns in one page.
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+/// One page of a [`Paginated`] endpoint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    /// The number of items across all pages, as of this page.
+    pub total_count: i64,
+    pub items: Vec<T>,
+}
+
+/// An [`Endpoint`] returning its items page by page, selected with `count`
+/// and `offset`.
+pub trait Paginated: Endpoint + Sized {
+    /// The items listed by the endpoint.
+    type Item;
+
+    /// How many items the endpoint can reach, counting the offset, such as
+    /// [`SEARCH_WINDOW`] for message searches and bounces. Pagination stops
+    /// there.
+    const WINDOW: Option<i64> = None;
+
+    /// The offset of the first item this request lists.
+    fn offset(&self) -> i64;
+
+    /// This request, for `count` items starting at `offset`.
+    fn page(&self, count: i64, offset: i64) -> Self;
+
+    /// Split a response into its total count and items.
+    fn into_page(response: Self::Response) -> Page<Self::Item>;
+
+    /// Every item, fetched page by page from the offset of this request,
+    /// see the [module documentation](self).
+    #[cfg(feature = "stream")]
+    fn paginate<C>(self, client: &C) -> Paginate<'_, Self, C>
+    where
+        C: crate::Client + Send + Sync,
+    {
+        Paginate::new(self, client)
+    }
+}
+
+/// A [`Paginated`] search filtering on a `fromdate`/`todate` range, which
+/// can be split to reach past the [`SEARCH_WINDOW`].
+pub trait TimeSliced: Paginated {
+    /// This request, for the items from `from_date` to `to_date`, both
+    /// included.
+    fn between(&self, from_date: String, to_date: String) -> Self;
+
+    /// Identifies an item across slices, so items found in two overlapping
+    /// slices are returned once.
+    fn item_id(item: &Self::Item) -> Cow<'_, str>;
+
+    /// Every item from `from` to `to`, both included, see the
+    /// [module documentation](self).
+    ///
+    /// Dates are sent with a precision of one second, in the time zone
+    /// Postmark uses for searches.
+    #[cfg(feature = "stream")]
+    fn search_all<C>(
+        self,
+        client: &C,
+        from: time::PrimitiveDateTime,
+        to: time::PrimitiveDateTime,
+    ) -> SearchAll<'_, Self, C>
+    where
+        C: crate::Client + Send + Sync,
+    {
+        SearchAll::new(self, client, from, to)
+    }
+}
+
+#[cfg(feature = "stream")]
+pub use stream::*;
+
+#[cfg(feature = "stream")]
+mod stream {
+    use std::collections::{HashSet, VecDeque};
+    use std::pin::Pin;
+    use std::task::{Context, Poll, ready};
+
+    use futures_core::Stream;
+    use futures_core::future::BoxFuture;
+    use thiserror::Error;
+    use time::format_description::BorrowedFormatItem;
+    use time::macros::format_description;
+    use time::{Duration, PrimitiveDateTime};
+
+    use super::{MAX_PAGE_SIZE, Page, Paginated, SEARCH_WINDOW, TimeSliced};
+    use crate::api::DEFAULT_PAGE_COUNT;
+    use crate::{Client, Query, QueryError};
+
+    type PageResult<E, C> = Result<Page<<E as Paginated>::Item>, QueryError<<C as Client>::Error>>;
+
+    /// A page request, in flight or answered but not read yet.
+    enum Fetch<'a, T> {
+        Pending(BoxFuture<'a, T>),
+        Done(T),
+    }
+
+    /// A [`Stream`] of every item of a [`Paginated`] endpoint, see
+    /// [`Paginated::paginate`].
+    ///
+    /// The stream ends after the first error.
+    pub struct Paginate<'a, E, C>
+    where
+        E: Paginated,
+        C: Client,
+    {
+        request: E,
+        client: &'a C,
+        page_size: i64,
+        prefetch: usize,
+        next_offset: i64,
+        total_count: Option<i64>,
+        pages: VecDeque<Fetch<'a, PageResult<E, C>>>,
+        items: VecDeque<E::Item>,
+        done: bool,
+    }
+
+    impl<'a, E, C> Paginate<'a, E, C>
+    where
+        E: Paginated,
+        C: Client,
+    {
+        pub(super) fn new(request: E, client: &'a C) -> Self {
+            Self {
+                next_offset: request.offset(),
+                request,
+                client,
+                page_size: DEFAULT_PAGE_COUNT,
+                prefetch: 0,
+                total_count: None,
+                pages: VecDeque::new(),
+                items: VecDeque::new(),
+                done: false,
+            }
+        }
+
+        /// Fetch `page_size` items per request, 100 by default and at most
+        /// [`MAX_PAGE_SIZE`].
+        ///
+        /// # Panics
+        ///
+        /// Panics when `page_size` is not positive.
+        pub fn page_size(mut self, page_size: i64) -> Self {
+            assert!(page_size > 0, "page size must be positive");
+            self.page_size = page_size.min(MAX_PAGE_SIZE);
+            self
+        }
+
+        /// Request up to `pages` pages ahead of the one being read, once the
+        /// first page told how many items there are. None by default, pages
+        /// are only requested when the previous one has been read.
+        pub fn prefetch(mut self, pages: usize) -> Self {
+            self.prefetch = pages;
+            self
+        }
+    }
+
+    impl<'a, E, C> Paginate<'a, E, C>
+    where
+        E: Paginated + Send + Sync + 'a,
+        E::Item: Send,
+        C: Client + Send + Sync,
+    {
+        fn fetch_more(&mut self) {
+            let end = E::WINDOW.unwrap_or(i64::MAX);
+            while !self.done && self.next_offset < end {
+                let more = match self.total_count {
+                    // Until the first page tells how many items there are,
+                    // only one request is sent.
+                    None => self.pages.is_empty(),
+                    Some(total) => {
+                        let reading = usize::from(self.items.is_empty());
+                        self.next_offset < total && self.pages.len() < self.prefetch + reading
+                    }
+                };
+                if !more {
+                    return;
+                }
+
+                let count = self.page_size.min(end - self.next_offset);
+                let page = self.request.page(count, self.next_offset);
+                let client = self.client;
+                self.pages.push_back(Fetch::Pending(Box::pin(async move {
+                    page.execute(client).await.map(E::into_page)
+                })));
+                self.next_offset += count;
+            }
+        }
+    }
+
+    // Pages are boxed and nothing else is ever pinned.
+    impl<E, C> Unpin for Paginate<'_, E, C>
+    where
+        E: Paginated,
+        C: Client,
+    {
+    }
+
+    impl<'a, E, C> Stream for Paginate<'a, E, C>
+    where
+        E: Paginated + Send + Sync + 'a,
+        E::Item: Send,
+        C: Client + Send + Sync,
+    {
+        type Item = Result<E::Item, QueryError<C::Error>>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+            loop {
+                this.fetch_more();
+                for fetch in &mut this.pages {
+                    if let Fetch::Pending(page) = fetch
+                        && let Poll::Ready(result) = page.as_mut().poll(cx)
+                    {
+                        *fetch = Fetch::Done(result);
+                    }
+                }
+
+                if let Some(item) = this.items.pop_front() {
+                    return Poll::Ready(Some(Ok(item)));
+                }
+
+                let result = match this.pages.pop_front() {
+                    Some(Fetch::Done(result)) => result,
+                    Some(pending @ Fetch::Pending(_)) => {
+                        this.pages.push_front(pending);
+                        return Poll::Pending;
+                    }
+                    None => return Poll::Ready(None),
+                };
+
+                match result {
+                    Ok(page) => {
+                        this.total_count = Some(page.total_count);
+                        if page.items.is_empty() {
+                            this.done = true;
+                            this.pages.clear();
+                        }
+                        this.items.extend(page.items);
+                    }
+                    Err(error) => {
+                        this.done = true;
+                        this.pages.clear();
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// An error from [`SearchAll`].
+    #[derive(Error, Debug)]
+    pub enum SearchError<E>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        /// A search request failed.
+        #[error("{}", source)]
+        Query { source: QueryError<E> },
+        /// More than [`SEARCH_WINDOW`] items fall between `from` and `to`,
+        /// one second apart, so their slice cannot be split any further.
+        #[error("{total_count} items from {from} to {to} do not fit in the search window")]
+        WindowExceeded {
+            from: PrimitiveDateTime,
+            to: PrimitiveDateTime,
+            total_count: i64,
+        },
+    }
+
+    const DATE_FORMAT: &[BorrowedFormatItem<'_>] =
+        format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+
+    fn format_date(date: PrimitiveDateTime) -> String {
+        date.format(DATE_FORMAT)
+            .expect("search dates format as ISO 8601")
+    }
+
+    /// A date range left to read, from `offset` on.
+    #[derive(Debug, Clone, Copy)]
+    struct Slice {
+        from: PrimitiveDateTime,
+        to: PrimitiveDateTime,
+        offset: i64,
+    }
+
+    impl Slice {
+        fn new(from: PrimitiveDateTime, to: PrimitiveDateTime) -> Self {
+            Self {
+                from,
+                to,
+                offset: 0,
+            }
+        }
+
+        /// Both halves of the range, unless it is at most a second long.
+        ///
+        /// The halves share their middle second, as items within that
+        /// second may be stamped past it.
+        fn split(self) -> Option<(Slice, Slice)> {
+            let length = self.to - self.from;
+            if length < 2 * Duration::SECOND {
+                return None;
+            }
+            let middle = self.from + Duration::seconds(length.whole_seconds() / 2);
+            Some((Slice::new(self.from, middle), Slice::new(middle, self.to)))
+        }
+    }
+
+    type Step<E, C> = (
+        Walk<E>,
+        Result<Vec<<E as Paginated>::Item>, SearchError<<C as Client>::Error>>,
+    );
+
+    /// The state of a [`SearchAll`], moved into each page request.
+    struct Walk<E> {
+        request: E,
+        page_size: i64,
+        /// Slices left to read, the next one last.
+        slices: Vec<Slice>,
+        /// Ids read from the current slice, and from the slices it was
+        /// split from.
+        seen: HashSet<String>,
+        /// Ids read from the previous slice, which shares a second with the
+        /// current one.
+        previous: HashSet<String>,
+    }
+
+    impl<E> Walk<E>
+    where
+        E: TimeSliced + Send + Sync,
+        E::Item: Send,
+    {
+        async fn step<C>(mut self, client: &C) -> Step<E, C>
+        where
+            C: Client + Send + Sync,
+        {
+            let result = self.read(client).await;
+            (self, result)
+        }
+
+        /// Read the next page of the next slice, or split the slice when it
+        /// does not fit in the search window.
+        async fn read<C>(&mut self, client: &C) -> Result<Vec<E::Item>, SearchError<C::Error>>
+        where
+            C: Client + Send + Sync,
+        {
+            let Some(slice) = self.slices.pop() else {
+                return Ok(Vec::new());
+            };
+
+            let count = self.page_size.min(SEARCH_WINDOW - slice.offset);
+            let page = self
+                .request
+                .between(format_date(slice.from), format_date(slice.to))
+                .page(count, slice.offset)
+                .execute(client)
+                .await
+                .map(E::into_page)
+                .map_err(|source| SearchError::Query { source })?;
+
+            if page.total_count > SEARCH_WINDOW {
+                // Items already read from the slice may be found again in
+                // its halves, so `seen` is kept.
+                let (first, second) = slice.split().ok_or(SearchError::WindowExceeded {
+                    from: slice.from,
+                    to: slice.to,
+                    total_count: page.total_count,
+                })?;
+                self.slices.push(second);
+                self.slices.push(first);
+                return Ok(Vec::new());
+            }
+
+            let offset = slice.offset + count;
+            let more = !page.items.is_empty() && offset < page.total_count;
+            let items = page
+                .items
+                .into_iter()
+                .filter(|item| {
+                    let id = E::item_id(item);
+                    let new = !self.previous.contains(id.as_ref());
+                    self.seen.insert(id.into_owned()) && new
+                })
+                .collect();
+
+            if more {
+                self.slices.push(Slice { offset, ..slice });
+            } else {
+                self.previous = std::mem::take(&mut self.seen);
+            }
+            Ok(items)
+        }
+    }
+
+    /// A [`Stream`] of every item of a [`TimeSliced`] search over a date
+    /// range, see [`TimeSliced::search_all`].
+    ///
+    /// The stream ends after the first error.
+    pub struct SearchAll<'a, E, C>
+    where
+        E: TimeSliced,
+        C: Client,
+    {
+        client: &'a C,
+        /// Taken while a page request is in flight.
+        walk: Option<Walk<E>>,
+        step: Option<BoxFuture<'a, Step<E, C>>>,
+        items: VecDeque<E::Item>,
+    }
+
+    impl<'a, E, C> SearchAll<'a, E, C>
+    where
+        E: TimeSliced,
+        C: Client,
+    {
+        pub(super) fn new(
+            request: E,
+            client: &'a C,
+            from: PrimitiveDateTime,
+            to: PrimitiveDateTime,
+        ) -> Self {
+            Self {
+                client,
+                walk: Some(Walk {
+                    request,
+                    page_size: DEFAULT_PAGE_COUNT,
+                    slices: vec![Slice::new(from, to)],
+                    seen: HashSet::new(),
+                    previous: HashSet::new(),
+                }),
+                step: None,
+                items: VecDeque::new(),
+            }
+        }
+
+        /// Fetch `page_size` items per request, 100 by default and at most
+        /// [`MAX_PAGE_SIZE`].
+        ///
+        /// # Panics
+        ///
+        /// Panics when `page_size` is not positive, or when called after
+        /// the stream was first polled.
+        pub fn page_size(mut self, page_size: i64) -> Self {
+            assert!(page_size > 0, "page size must be positive");
+            self.walk
+                .as_mut()
+                .expect("page size is set before polling")
+                .page_size = page_size.min(MAX_PAGE_SIZE);
+            self
+        }
+    }
+
+    // Page requests are boxed and nothing else is ever pinned.
+    impl<E, C> Unpin for SearchAll<'_, E, C>
+    where
+        E: TimeSliced,
+        C: Client,
+    {
+    }
+
+    impl<'a, E, C> Stream for SearchAll<'a, E, C>
+    where
+        E: TimeSliced + Send + Sync + 'a,
+        E::Item: Send,
+        C: Client + Send + Sync,
+    {
+        type Item = Result<E::Item, SearchError<C::Error>>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+            loop {
+                if let Some(item) = this.items.pop_front() {
+                    return Poll::Ready(Some(Ok(item)));
+                }
+
+                let step = match &mut this.step {
+                    Some(step) => step,
+                    None => {
+                        let walk = this.walk.take().expect("walk is back between steps");
+                        if walk.slices.is_empty() {
+                            this.walk = Some(walk);
+                            return Poll::Ready(None);
+                        }
+                        this.step.insert(Box::pin(walk.step(this.client)))
+                    }
+                };
+
+                let (mut walk, result) = ready!(step.as_mut().poll(cx));
+                this.step = None;
+                match result {
+                    Ok(items) => {
+                        this.walk = Some(walk);
+                        this.items.extend(items);
+                    }
+                    Err(error) => {
+                        walk.slices.clear();
+                        this.walk = Some(walk);
+                        return Poll::Ready(Some(Err(error)));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "stream"))]
+mod tests {
+    use std::collections::HashSet;
+    use std::convert::Infallible;
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use bytes::Bytes;
+    use futures_util::{StreamExt, TryStreamExt};
+    use http::{Method, Request, Response, StatusCode};
+    use serde_json::json;
+    use time::macros::{datetime, format_description};
+    use time::{Duration, PrimitiveDateTime};
+
+    use super::*;
+    use crate::Client;
+    use crate::api::messages::OutboundSearchRequest;
+    use crate::api::templates::ListTemplatesRequest;
+    use crate::testing::{MockClient, MockResponse};
+
+    fn templates(total: usize, ids: std::ops::Range<usize>) -> MockResponse {
+        MockResponse::ok(json!({
+            "TotalCount": total,
+            "Templates": ids.map(|id| json!({
+                "Active": true,
+                "TemplateId": id,
+                "Name": format!("template {id}"),
+                "Alias": null,
+                "TemplateType": "Standard",
+                "LayoutTemplate": null,
+            })).collect::<Vec<_>>(),
+        }))
+    }
+
+    fn offsets(client: &MockClient) -> Vec<String> {
+        client
+            .requests()
+            .iter()
+            .map(|req| req.uri().query().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn streams_every_item_page_by_page() {
+        let client = MockClient::new();
+        client
+            .respond(Method::GET, "/templates", templates(5, 0..2))
+            .respond(Method::GET, "/templates", templates(5, 2..4))
+            .respond(Method::GET, "/templates", templates(5, 4..5));
+
+        let names: Vec<_> = ListTemplatesRequest::builder()
+            .build()
+            .paginate(&client)
+            .page_size(2)
+            .map_ok(|template| template.name)
+            .try_collect()
+            .await
+            .expect("templates");
+
+        assert_eq!(names.len(), 5);
+        assert_eq!(names[4], "template 4");
+        assert_eq!(
+            offsets(&client),
+            ["count=2&offset=0", "count=2&offset=2", "count=2&offset=4"]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetches_lazily_and_stops_after_an_error() {
+        let client = MockClient::new();
+        client
+            .respond(Method::GET, "/templates", templates(10, 0..2))
+            .respond(Method::GET, "/templates", MockResponse::server_error());
+
+        let mut stream = ListTemplatesRequest::builder()
+            .offset(4)
+            .build()
+            .paginate(&client)
+            .page_size(2);
+
+        stream.next().await.expect("item").expect("template");
+        assert_eq!(offsets(&client), ["count=2&offset=4"]);
+
+        stream.next().await.expect("item").expect("template");
+        stream
+            .next()
+            .await
+            .expect("error")
+            .expect_err("server error");
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn prefetches_once_the_total_is_known() {
+        let client = MockClient::new();
+        client
+            .respond(Method::GET, "/templates", templates(6, 0..2))
+            .respond(Method::GET, "/templates", templates(6, 2..4))
+            .respond(Method::GET, "/templates", templates(6, 4..6));
+
+        let mut stream = ListTemplatesRequest::builder()
+            .build()
+            .paginate(&client)
+            .page_size(2)
+            .prefetch(2);
+
+        stream.next().await.expect("item").expect("template");
+        assert_eq!(
+            offsets(&client),
+            ["count=2&offset=0", "count=2&offset=2", "count=2&offset=4"]
+        );
+        assert_eq!(stream.count().await, 5);
+    }
+
+    #[tokio::test]
+    async fn searches_stop_at_the_search_window() {
+        let messages = |ids: std::ops::Range<usize>| {
+            MockResponse::ok(json!({
+                "TotalCount": 20_000,
+                "Messages": ids.map(|id| json!({ "MessageID": format!("message-{id}") })).collect::<Vec<_>>(),
+            }))
+        };
+        let client = MockClient::new();
+        client
+            .respond(Method::GET, "/messages/outbound", messages(9_400..9_900))
+            .respond(Method::GET, "/messages/outbound", messages(9_900..10_000));
+
+        let count = OutboundSearchRequest {
+            offset: Some(9_400),
+            ..Default::default()
+        }
+        .paginate(&client)
+        .page_size(1_000)
+        .try_collect::<Vec<_>>()
+        .await
+        .expect("messages")
+        .len();
+
+        assert_eq!(count, 600);
+        let pages: Vec<_> = client
+            .requests()
+            .iter()
+            .map(|req| {
+                let query = req.uri().query().unwrap_or_default();
+                url::form_urlencoded::parse(query.as_bytes())
+                    .filter(|(key, _)| key == "count" || key == "offset")
+                    .map(|(key, value)| format!("{key}={value}"))
+                    .collect::<Vec<_>>()
+                    .join("&")
+            })
+            .collect();
+        assert_eq!(pages, ["count=500&offset=9400", "count=100&offset=9900"]);
+    }
+
+    /// Answers outbound searches from its messages, newest first, like
+    /// Postmark does.
+    #[derive(Default)]
+    struct Mailbox {
+        messages: Mutex<Vec<(PrimitiveDateTime, String)>>,
+        /// Messages sent while the search runs, one per request.
+        arriving: Mutex<Vec<(PrimitiveDateTime, String)>>,
+        queries: Mutex<Vec<(String, String, i64, i64)>>,
+    }
+
+    impl Mailbox {
+        fn new(messages: impl IntoIterator<Item = (PrimitiveDateTime, String)>) -> Self {
+            let mut messages: Vec<_> = messages.into_iter().collect();
+            messages.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+            Self {
+                messages: Mutex::new(messages),
+                ..Self::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Client for Mailbox {
+        type Error = Infallible;
+
+        async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+            let query: std::collections::HashMap<_, _> =
+                url::form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+                    .into_owned()
+                    .collect();
+            let date = |key: &str| {
+                let format = format_description!("[year]-[month]-[day]T[hour]:[minute]:[second]");
+                PrimitiveDateTime::parse(&query[key], format).expect("search date")
+            };
+            let (from, to) = (date("fromdate"), date("todate"));
+            let count: i64 = query["count"].parse().expect("count");
+            let offset: i64 = query["offset"].parse().expect("offset");
+            self.queries.lock().unwrap().push((
+                query["fromdate"].clone(),
+                query["todate"].clone(),
+                count,
+                offset,
+            ));
+
+            let status = if offset + count > SEARCH_WINDOW {
+                StatusCode::UNPROCESSABLE_ENTITY
+            } else {
+                StatusCode::OK
+            };
+            let matching: Vec<_> = self
+                .messages
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(at, _)| (from..=to).contains(at))
+                .map(|(_, id)| id.clone())
+                .collect();
+            let body = json!({
+                "TotalCount": matching.len(),
+                "Messages": matching
+                    .iter()
+                    .skip(offset as usize)
+                    .take(count as usize)
+                    .map(|id| json!({ "MessageID": id }))
+                    .collect::<Vec<_>>(),
+            });
+
+            if let Some(arrived) = self.arriving.lock().unwrap().pop() {
+                self.messages.lock().unwrap().insert(0, arrived);
+            }
+            Ok(Response::builder()
+                .status(status)
+                .body(Bytes::from(body.to_string()))
+                .expect("response"))
+        }
+    }
+
+    #[tokio::test]
+    async fn search_all_slices_the_range_past_the_search_window() {
+        let start = datetime!(2024-01-01 0:00);
+        let mailbox = Mailbox::new(
+            (0..25_000).map(|i| (start + Duration::seconds(3 * i), format!("message-{i}"))),
+        );
+
+        let ids: Vec<_> = OutboundSearchRequest::default()
+            .search_all(&mailbox, start, datetime!(2024-01-01 23:59:59))
+            .page_size(500)
+            .map_ok(|message| message.message_id)
+            .try_collect()
+            .await
+            .expect("messages");
+
+        assert_eq!(ids.len(), 25_000);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 25_000);
+
+        let queries = mailbox.queries.lock().unwrap();
+        assert_eq!(queries[0].0, "2024-01-01T00:00:00");
+        assert_eq!(queries[0].1, "2024-01-01T23:59:59");
+        assert!(
+            queries
+                .iter()
+                .all(|(_, _, count, offset)| count + offset <= SEARCH_WINDOW)
+        );
+    }
+
+    #[tokio::test]
+    async fn search_all_skips_messages_seen_in_an_earlier_page() {
+        let start = datetime!(2024-01-01 0:00);
+        let end = datetime!(2024-01-01 1:00);
+        let mailbox =
+            Mailbox::new((0..7).map(|i| (start + Duration::minutes(i), format!("message-{i}"))));
+        // New messages push older ones to later pages while paging.
+        mailbox
+            .arriving
+            .lock()
+            .unwrap()
+            .extend((0..3).map(|i| (end, format!("new-{i}"))));
+
+        let ids: Vec<_> = OutboundSearchRequest::default()
+            .search_all(&mailbox, start, end)
+            .page_size(3)
+            .map_ok(|message| message.message_id)
+            .try_collect()
+            .await
+            .expect("messages");
+
+        let expected: Vec<_> = (0..7).rev().map(|i| format!("message-{i}")).collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[tokio::test]
+    async fn search_all_skips_messages_seen_before_a_slice_is_split() {
+        let start = datetime!(2024-01-01 0:00);
+        let end = datetime!(2024-01-01 3:00);
+        let mailbox = Mailbox::new(
+            (0..SEARCH_WINDOW - 1).map(|i| (start + Duration::seconds(i), format!("message-{i}"))),
+        );
+        // The slice only overflows the window after two pages were read.
+        mailbox
+            .arriving
+            .lock()
+            .unwrap()
+            .extend((0..2).map(|i| (end, format!("new-{i}"))));
+
+        let ids: Vec<_> = OutboundSearchRequest::default()
+            .search_all(&mailbox, start, end)
+            .page_size(500)
+            .map_ok(|message| message.message_id)
+            .try_collect()
+            .await
+            .expect("messages");
+
+        assert_eq!(ids.len(), SEARCH_WINDOW as usize + 1);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    }
+
+    #[tokio::test]
+    async fn search_all_reads_messages_on_the_split_second_once() {
+        let start = datetime!(2024-01-01 0:00);
+        let middle = datetime!(2024-01-01 0:30);
+        // Both halves of the range hold the messages of its middle second.
+        let mailbox = Mailbox::new(
+            (0..SEARCH_WINDOW - 5)
+                .map(|i| (middle, format!("message-{i}")))
+                .chain((0..10).map(|i| (start + Duration::minutes(i), format!("early-{i}")))),
+        );
+
+        let ids: Vec<_> = OutboundSearchRequest::default()
+            .search_all(&mailbox, start, datetime!(2024-01-01 1:00))
+            .map_ok(|message| message.message_id)
+            .try_collect()
+            .await
+            .expect("messages");
+
+        assert_eq!(ids.len(), SEARCH_WINDOW as usize + 5);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    }
+
+    #[tokio::test]
+    async fn search_all_fails_when_one_second_overflows_the_window() {
+        let at = datetime!(2024-01-01 12:00);
+        let mailbox = Mailbox::new((0..=SEARCH_WINDOW).map(|i| (at, format!("message-{i}"))));
+
+        let mut messages = OutboundSearchRequest::default().search_all(
+            &mailbox,
+            datetime!(2024-01-01 0:00),
+            datetime!(2024-01-02 0:00),
+        );
+
+        let error = messages.next().await.expect("error").expect_err("window");
+        assert!(matches!(
+            error,
+            SearchError::WindowExceeded { from, to, total_count: 10_001 }
+                if from <= at && at <= to && to - from == Duration::SECOND
+        ));
+        assert!(messages.next().await.is_none());
+    }
+}
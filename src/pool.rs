@@ -0,0 +1,364 @@
+//! Clients for every server of an account, from the account token alone.
+//!
+//! A [`ServerPool`] wraps a [`Client`] authenticated with the account token.
+//! It looks up each server's API token through the servers API the first
+//! time the server is used, caches it, and hands out [`ServerClient`]s that
+//! send requests with it.
+//!
+//! ```no_run
+//! use postmark::Query;
+//! use postmark::api::bounce::GetDeliveryStatsRequest;
+//! use postmark::pool::ServerPool;
+//! use postmark::reqwest::PostmarkClient;
+//!
+//! # async fn f() -> Result<(), Box<dyn std::error::Error>> {
+//! let pool = ServerPool::new(
+//!     PostmarkClient::builder()
+//!         .account_token("<account token>")
+//!         .build(),
+//! );
+//!
+//! for server in pool.servers().await? {
+//!     let client = pool.server(server.id).await?;
+//!     let stats = GetDeliveryStatsRequest::default().execute(&client).await?;
+//!     println!("{}: {} inactive", server.name, stats.inactive_mails);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Request, Response, StatusCode};
+use thiserror::Error;
+
+use crate::api::server::{GetServerRequest, ListServersRequest, Server, ServerIdOrName};
+use crate::{Client, Query, QueryError, ServerClient, ServerTokenOverride};
+
+/// The error returned by the clients of a [`ServerPool`].
+#[derive(Error, Debug)]
+pub enum ServerPoolError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    /// The wrapped client failed.
+    #[error("client error: {}", source)]
+    Client { source: E },
+    /// The server's token could not be looked up.
+    #[error("could not look up the token of server {server}: {}", source)]
+    TokenLookup {
+        server: ServerIdOrName,
+        source: Box<QueryError<E>>,
+    },
+    /// The server has no API token.
+    #[error("server {server} has no api token")]
+    NoToken { server: ServerIdOrName },
+}
+
+/// Resolves and caches the server tokens of an account.
+///
+/// Tokens are cached by the [`ServerIdOrName`] they were requested with.
+/// When a server answers `401 Unauthorized`, for instance because it was
+/// recreated with new tokens, its token is looked up again and the request
+/// sent once more.
+pub struct ServerPool<C> {
+    client: C,
+    tokens: Mutex<HashMap<ServerIdOrName, String>>,
+}
+
+impl<C> std::fmt::Debug for ServerPool<C>
+where
+    C: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerPool")
+            .field("client", &self.client)
+            .field("servers", &self.lock().keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<C> ServerPool<C> {
+    /// Create a pool from a client authenticated with the account token.
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The account client.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+
+    /// Forget the cached token of `server`.
+    pub fn invalidate(&self, server: impl Into<ServerIdOrName>) {
+        self.lock().remove(&server.into());
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ServerIdOrName, String>> {
+        self.tokens.lock().expect("server pool tokens poisoned")
+    }
+
+    fn cache(&self, server: &Server) {
+        if let Some(token) = server.api_tokens.first() {
+            let mut tokens = self.lock();
+            tokens.insert(ServerIdOrName::ServerId(server.id), token.clone());
+            tokens.insert(
+                ServerIdOrName::ServerName(server.name.clone()),
+                token.clone(),
+            );
+        }
+    }
+}
+
+impl<C> ServerPool<C>
+where
+    C: Client + Send + Sync,
+{
+    /// Every server of the account, caching their tokens along the way.
+    pub async fn servers(&self) -> Result<Vec<Server>, QueryError<C::Error>> {
+        let mut servers = Vec::new();
+
+        loop {
+            let page = ListServersRequest::builder()
+                .offset(servers.len() as i64)
+                .build()
+                .execute(&self.client)
+                .await?;
+            let last_page = page.servers.is_empty();
+
+            for server in &page.servers {
+                self.cache(server);
+            }
+            servers.extend(page.servers);
+
+            if last_page || servers.len() as i64 >= page.total_count {
+                return Ok(servers);
+            }
+        }
+    }
+
+    /// The token of `server`, looked up when it is not cached yet.
+    pub async fn token(
+        &self,
+        server: impl Into<ServerIdOrName>,
+    ) -> Result<String, ServerPoolError<C::Error>> {
+        let server = server.into();
+        if let Some(token) = self.lock().get(&server) {
+            return Ok(token.clone());
+        }
+
+        self.refresh(server).await
+    }
+
+    /// Look up the token of `server` again, replacing the cached one.
+    pub async fn refresh(
+        &self,
+        server: impl Into<ServerIdOrName>,
+    ) -> Result<String, ServerPoolError<C::Error>> {
+        let server = server.into();
+        let found = GetServerRequest::builder()
+            .server_id(server.clone())
+            .build()
+            .execute(&self.client)
+            .await
+            .map_err(|source| ServerPoolError::TokenLookup {
+                server: server.clone(),
+                source: Box::new(source),
+            })?;
+
+        let token = found
+            .api_tokens
+            .first()
+            .cloned()
+            .ok_or_else(|| ServerPoolError::NoToken {
+                server: server.clone(),
+            })?;
+
+        self.cache(&found);
+        self.lock().insert(server, token.clone());
+        Ok(token)
+    }
+
+    /// A client for the endpoints of `server`.
+    pub async fn server(
+        &self,
+        server: impl Into<ServerIdOrName>,
+    ) -> Result<ServerClient<PooledClient<'_, C>>, ServerPoolError<C::Error>> {
+        let server = server.into();
+        self.token(server.clone()).await?;
+        Ok(ServerClient::new(PooledClient { pool: self, server }))
+    }
+}
+
+/// A [`Client`] sending requests with the token of one server of a
+/// [`ServerPool`], see [`ServerPool::server`].
+#[derive(Debug)]
+pub struct PooledClient<'a, C> {
+    pool: &'a ServerPool<C>,
+    server: ServerIdOrName,
+}
+
+impl<C> PooledClient<'_, C> {
+    /// The server requests are sent to.
+    pub fn server(&self) -> &ServerIdOrName {
+        &self.server
+    }
+}
+
+#[async_trait]
+impl<C> Client for PooledClient<'_, C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = ServerPoolError<C::Error>;
+
+    async fn execute(&self, mut req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let token = self.pool.token(self.server.clone()).await?;
+        ServerTokenOverride(token).apply(&mut req);
+
+        let rsp = self
+            .pool
+            .client
+            .execute(req.clone())
+            .await
+            .map_err(|source| ServerPoolError::Client { source })?;
+        if rsp.status() != StatusCode::UNAUTHORIZED {
+            return Ok(rsp);
+        }
+
+        let token = self.pool.refresh(self.server.clone()).await?;
+        ServerTokenOverride(token).apply(&mut req);
+
+        self.pool
+            .client
+            .execute(req)
+            .await
+            .map_err(|source| ServerPoolError::Client { source })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Method;
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::templates::{ListTemplatesRequest, ListTemplatesResponse};
+    use crate::testing::{MockClient, MockResponse};
+
+    fn server(id: i64, name: &str, token: &str) -> serde_json::Value {
+        json!({ "ID": id, "Name": name, "ApiTokens": [token] })
+    }
+
+    fn templates() -> MockResponse {
+        MockResponse::ok(ListTemplatesResponse {
+            total_count: 0,
+            templates: vec![],
+        })
+    }
+
+    fn sent_tokens(client: &MockClient) -> Vec<String> {
+        client
+            .requests_to(Method::GET, "/templates")
+            .iter()
+            .map(|req| {
+                let token = req
+                    .extensions()
+                    .get::<ServerTokenOverride>()
+                    .map(|token| token.0.clone())
+                    .expect("token override");
+                assert_eq!(req.headers()["X-Postmark-Server-Token"], token.as_str());
+                token
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn looks_up_tokens_once_and_caches_them() {
+        let account = MockClient::new();
+        account
+            .respond(
+                Method::GET,
+                "/servers/1",
+                MockResponse::ok(server(1, "one", "token-1")),
+            )
+            .respond(Method::GET, "/templates", templates());
+
+        let pool = ServerPool::new(account.clone());
+        let client = pool.server(1).await.expect("server client");
+        for _ in 0..2 {
+            ListTemplatesRequest::builder()
+                .build()
+                .execute(&client)
+                .await
+                .expect("templates");
+        }
+
+        assert_eq!(account.requests_to(Method::GET, "/servers/1").len(), 1);
+        assert_eq!(sent_tokens(&account), ["token-1", "token-1"]);
+    }
+
+    #[tokio::test]
+    async fn refreshes_the_token_on_unauthorized() {
+        let account = MockClient::new();
+        account
+            .respond(
+                Method::GET,
+                "/servers/1",
+                MockResponse::ok(server(1, "one", "old")),
+            )
+            .respond(
+                Method::GET,
+                "/servers/1",
+                MockResponse::ok(server(1, "one", "new")),
+            )
+            .respond(
+                Method::GET,
+                "/templates",
+                MockResponse::api_error(StatusCode::UNAUTHORIZED, 10, "Bad or missing API token"),
+            )
+            .respond(Method::GET, "/templates", templates());
+
+        let pool = ServerPool::new(account.clone());
+        ListTemplatesRequest::builder()
+            .build()
+            .execute(&pool.server(1).await.expect("server client"))
+            .await
+            .expect("templates after refresh");
+
+        assert_eq!(sent_tokens(&account), ["old", "new"]);
+        assert_eq!(pool.token(1).await.expect("token"), "new");
+    }
+
+    #[tokio::test]
+    async fn listing_servers_caches_every_token() {
+        let account = MockClient::new();
+        account.respond(
+            Method::GET,
+            "/servers",
+            MockResponse::ok(json!({
+                "TotalCount": 2,
+                "Servers": [server(1, "one", "token-1"), server(2, "two", "token-2")],
+            })),
+        );
+
+        let pool = ServerPool::new(account.clone());
+        let servers = pool.servers().await.expect("servers");
+        assert_eq!(servers.len(), 2);
+
+        assert_eq!(pool.token(2).await.expect("token"), "token-2");
+        assert_eq!(
+            pool.token(ServerIdOrName::ServerName("one".into()))
+                .await
+                .expect("token"),
+            "token-1"
+        );
+        account.assert_not_sent(Method::GET, "/servers/2");
+    }
+}
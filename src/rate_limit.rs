@@ -0,0 +1,436 @@
+//! A [`Client`] decorator keeping request rates under Postmark's limits.
+//!
+//! [`RateLimitedClient`] holds a token bucket per server token and
+//! [`EndpointFamily`], shared by every task using the client. Requests wait
+//! for a token before they are sent, and a `429 Too Many Requests` pauses the
+//! bucket it came from, for the `Retry-After` delay when Postmark gives one.
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use postmark::rate_limit::{EndpointFamily, Rate, RateLimitedClient};
+//! use std::time::Duration;
+//!
+//! let client = RateLimitedClient::new(PostmarkClient::default(), Rate::per_second(50))
+//!     .with_limit(EndpointFamily::Batch, Rate::new(10, Duration::from_secs(1)))
+//!     .with_limit(EndpointFamily::Messages, Rate::per_second(5))
+//!     .with_token_limit("<busy server token>", EndpointFamily::Send, Rate::per_second(100));
+//! ```
+//!
+//! Rates apply to each token on its own: every server token gets the same
+//! rates unless [`RateLimitedClient::with_token_limit`] sets its own.
+//!
+//! The request that got the `429` is returned as is. Wrap the rate limited
+//! client in a `RetryClient` to send it again once the bucket reopens.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Request, Response, StatusCode};
+use tokio::time::Instant;
+
+use crate::client::retry_after;
+use crate::{Client, EndpointInfo, ServerTokenOverride};
+
+/// Groups of endpoints sharing a bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointFamily {
+    /// Single sends: `/email` and `/email/withTemplate`.
+    Send,
+    /// Batch sends: `/email/batch` and `/email/batchWithTemplates`.
+    Batch,
+    /// Bulk sends and their status: `/email/bulk`.
+    Bulk,
+    /// Message search, details, opens and clicks: `/messages/...`.
+    Messages,
+    /// Every other endpoint.
+    Other,
+}
+
+impl EndpointFamily {
+    /// The family of a request path or [`Endpoint::path_template`](crate::Endpoint::path_template).
+    pub fn of(path: &str) -> Self {
+        let path = path.split('?').next().unwrap_or_default();
+        match path {
+            "/email" | "/email/withTemplate" => Self::Send,
+            "/email/batch" | "/email/batchWithTemplates" => Self::Batch,
+            _ if path.starts_with("/email/bulk") => Self::Bulk,
+            _ if path.starts_with("/messages") => Self::Messages,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A sustained request rate, allowing bursts of up to `requests` at once.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rate {
+    requests: u32,
+    per: Duration,
+}
+
+impl Rate {
+    /// `requests` every `per`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `requests` or `per` is zero.
+    pub fn new(requests: u32, per: Duration) -> Self {
+        assert!(requests > 0, "a rate needs at least one request");
+        assert!(!per.is_zero(), "a rate needs a non zero period");
+        Self { requests, per }
+    }
+
+    /// `requests` every second.
+    pub fn per_second(requests: u32) -> Self {
+        Self::new(requests, Duration::from_secs(1))
+    }
+
+    fn interval(&self) -> Duration {
+        self.per / self.requests
+    }
+}
+
+/// Pause applied after a `429` without a `Retry-After` header, doubled for
+/// every `429` in a row.
+const INITIAL_PAUSE: Duration = Duration::from_secs(1);
+const MAX_PAUSE: Duration = Duration::from_secs(60);
+
+struct Bucket {
+    rate: Rate,
+    tokens: f64,
+    refilled_at: Instant,
+    paused_until: Option<Instant>,
+    throttled: u32,
+}
+
+impl Bucket {
+    fn new(rate: Rate, now: Instant) -> Self {
+        Self {
+            rate,
+            tokens: rate.requests as f64,
+            refilled_at: now,
+            paused_until: None,
+            throttled: 0,
+        }
+    }
+
+    /// Take a token, or return how long to wait before trying again.
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        if let Some(until) = self.paused_until {
+            if until > now {
+                return Err(until - now);
+            }
+            self.paused_until = None;
+        }
+
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() / self.rate.interval().as_secs_f64())
+            .min(self.rate.requests as f64);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(self.rate.interval().mul_f64(1.0 - self.tokens))
+        }
+    }
+
+    fn throttle(&mut self, now: Instant, retry_after: Option<Duration>) {
+        let pause = retry_after.unwrap_or_else(|| {
+            INITIAL_PAUSE
+                .saturating_mul(1 << self.throttled.min(6))
+                .min(MAX_PAUSE)
+        });
+        self.throttled = self.throttled.saturating_add(1);
+        self.paused_until = Some(now + pause);
+        self.tokens = 0.0;
+    }
+}
+
+type BucketKey = (Option<String>, EndpointFamily);
+
+/// Wraps a [`Client`] and spaces out its requests, see the
+/// [module documentation](self).
+///
+/// Each server token, as set by a
+/// [`ServerTokenOverride`](crate::ServerTokenOverride), gets its own buckets;
+/// requests without one share the buckets of the wrapped client's token.
+pub struct RateLimitedClient<C> {
+    inner: C,
+    default_rate: Rate,
+    rates: HashMap<EndpointFamily, Rate>,
+    token_rates: HashMap<(String, EndpointFamily), Rate>,
+    buckets: Mutex<HashMap<BucketKey, Bucket>>,
+}
+
+impl<C> std::fmt::Debug for RateLimitedClient<C>
+where
+    C: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitedClient")
+            .field("inner", &self.inner)
+            .field("default_rate", &self.default_rate)
+            .field("rates", &self.rates)
+            .field("token_rates", &self.token_rates.len())
+            .finish()
+    }
+}
+
+impl<C> RateLimitedClient<C> {
+    /// Limit every endpoint family to `rate`.
+    pub fn new(inner: C, rate: Rate) -> Self {
+        Self {
+            inner,
+            default_rate: rate,
+            rates: HashMap::new(),
+            token_rates: HashMap::new(),
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Limit `family` to `rate` instead of the default rate.
+    pub fn with_limit(mut self, family: EndpointFamily, rate: Rate) -> Self {
+        self.rates.insert(family, rate);
+        self
+    }
+
+    /// Limit `family` to `rate` for requests sent with the server token
+    /// `token`, instead of the rate other tokens get.
+    pub fn with_token_limit(
+        mut self,
+        token: impl Into<String>,
+        family: EndpointFamily,
+        rate: Rate,
+    ) -> Self {
+        self.token_rates.insert((token.into(), family), rate);
+        self
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The rate applied to `family`.
+    pub fn rate(&self, family: EndpointFamily) -> Rate {
+        self.rates
+            .get(&family)
+            .copied()
+            .unwrap_or(self.default_rate)
+    }
+
+    /// The rate applied to `family` for requests sent with the server token
+    /// `token`.
+    pub fn token_rate(&self, token: &str, family: EndpointFamily) -> Rate {
+        self.token_rates
+            .get(&(token.to_string(), family))
+            .copied()
+            .unwrap_or_else(|| self.rate(family))
+    }
+
+    fn with_bucket<T>(&self, key: &BucketKey, f: impl FnOnce(&mut Bucket, Instant) -> T) -> T {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().expect("rate limiter buckets poisoned");
+        let bucket = buckets.entry(key.clone()).or_insert_with(|| {
+            let rate = match &key.0 {
+                Some(token) => self.token_rate(token, key.1),
+                None => self.rate(key.1),
+            };
+            Bucket::new(rate, now)
+        });
+        f(bucket, now)
+    }
+}
+
+#[async_trait]
+impl<C> Client for RateLimitedClient<C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = C::Error;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let family = match req.extensions().get::<EndpointInfo>() {
+            Some(info) => EndpointFamily::of(&info.path_template),
+            None => EndpointFamily::of(req.uri().path()),
+        };
+        let token = req
+            .extensions()
+            .get::<ServerTokenOverride>()
+            .map(|token| token.0.clone());
+        let key = (token, family);
+
+        while let Err(wait) = self.with_bucket(&key, Bucket::try_acquire) {
+            tokio::time::sleep(wait).await;
+        }
+
+        let rsp = self.inner.execute(req).await?;
+        if rsp.status() == StatusCode::TOO_MANY_REQUESTS {
+            let delay = retry_after(rsp.headers());
+            self.with_bucket(&key, |bucket, now| bucket.throttle(now, delay));
+        } else {
+            self.with_bucket(&key, |bucket, _| bucket.throttled = 0);
+        }
+
+        Ok(rsp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use http::Method;
+
+    use super::*;
+    use crate::Query;
+    use crate::api::email::{SendEmailBatchRequest, SendEmailResponse};
+    use crate::api::templates::{ListTemplatesRequest, ListTemplatesResponse};
+    use crate::testing::{MockClient, MockResponse};
+
+    #[test]
+    fn endpoint_families() {
+        assert_eq!(EndpointFamily::of("/email"), EndpointFamily::Send);
+        assert_eq!(
+            EndpointFamily::of("/email/batchWithTemplates"),
+            EndpointFamily::Batch
+        );
+        assert_eq!(EndpointFamily::of("/email/bulk/{id}"), EndpointFamily::Bulk);
+        assert_eq!(
+            EndpointFamily::of("/messages/outbound?count=10"),
+            EndpointFamily::Messages
+        );
+        assert_eq!(EndpointFamily::of("/templates/{id}"), EndpointFamily::Other);
+    }
+
+    fn client() -> (MockClient, Arc<RateLimitedClient<MockClient>>) {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::ok(Vec::<SendEmailResponse>::new()),
+        )
+        .respond(
+            Method::GET,
+            "/templates",
+            MockResponse::ok(ListTemplatesResponse {
+                total_count: 0,
+                templates: vec![],
+            }),
+        );
+
+        let limited = RateLimitedClient::new(mock.clone(), Rate::per_second(100))
+            .with_limit(EndpointFamily::Batch, Rate::per_second(2));
+        (mock, Arc::new(limited))
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn spaces_requests_per_family() {
+        let (_, client) = client();
+        let start = Instant::now();
+
+        let batches: Vec<_> = (0..4)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let batch: SendEmailBatchRequest = Vec::new();
+                    batch.execute(client.as_ref()).await.expect("batch")
+                })
+            })
+            .collect();
+        for batch in batches {
+            batch.await.expect("join");
+        }
+        // Two requests fit in the initial burst, the next two wait 500ms each.
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+        let start = Instant::now();
+        ListTemplatesRequest::builder()
+            .build()
+            .execute(client.as_ref())
+            .await
+            .expect("templates");
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn too_many_requests_pauses_the_bucket() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::json(StatusCode::TOO_MANY_REQUESTS, ()),
+        )
+        .respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::ok(Vec::<SendEmailResponse>::new()),
+        );
+        let client = RateLimitedClient::new(mock.clone(), Rate::per_second(100));
+
+        let batch: SendEmailBatchRequest = Vec::new();
+        batch.execute(&client).await.expect_err("rate limited");
+
+        let start = Instant::now();
+        let batch: SendEmailBatchRequest = Vec::new();
+        batch.execute(&client).await.expect("batch");
+        assert_eq!(start.elapsed(), INITIAL_PAUSE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn server_tokens_have_their_own_buckets() {
+        let (mock, client) = client();
+        let start = Instant::now();
+
+        for token in ["a", "b", "c"] {
+            let batch: SendEmailBatchRequest = Vec::new();
+            for _ in 0..2 {
+                batch
+                    .clone()
+                    .execute(&crate::ScopedClient::new(client.as_ref(), token))
+                    .await
+                    .expect("batch");
+            }
+        }
+
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(mock.requests().len(), 6);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn server_tokens_can_have_their_own_rate() {
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email/batch",
+            MockResponse::ok(Vec::<SendEmailResponse>::new()),
+        );
+        let client = RateLimitedClient::new(mock, Rate::per_second(100))
+            .with_limit(EndpointFamily::Batch, Rate::per_second(2))
+            .with_token_limit("busy", EndpointFamily::Batch, Rate::per_second(4));
+        assert_eq!(
+            client.token_rate("busy", EndpointFamily::Batch),
+            Rate::per_second(4)
+        );
+        assert_eq!(
+            client.token_rate("quiet", EndpointFamily::Batch),
+            Rate::per_second(2)
+        );
+
+        for (token, elapsed) in [("busy", Duration::ZERO), ("quiet", Duration::from_secs(1))] {
+            let start = Instant::now();
+            for _ in 0..4 {
+                let batch: SendEmailBatchRequest = Vec::new();
+                batch
+                    .execute(&crate::ScopedClient::new(&client, token))
+                    .await
+                    .expect("batch");
+            }
+            assert_eq!(start.elapsed(), elapsed, "{token}");
+        }
+    }
+}
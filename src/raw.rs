@@ -0,0 +1,235 @@
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use serde_json::Value;
+use url::form_urlencoded::Serializer;
+
+use crate::api::endpoint_with_query;
+use crate::{Endpoint, ServerToken, TokenScope};
+
+/// Wraps an [`Endpoint`] so that executing it sends the same request but
+/// returns the response as a [`serde_json::Value`].
+///
+/// Useful to read fields Postmark added to a response before this crate
+/// did.
+///
+/// ```
+/// # use postmark::reqwest::PostmarkClient;
+/// # use postmark::api::templates::{GetTemplateRequest, TemplateIdOrAlias};
+/// use postmark::{Query, Raw};
+///
+/// # async fn f(client: &PostmarkClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let template = Raw(GetTemplateRequest::builder()
+///     .id(TemplateIdOrAlias::Alias("welcome".into()))
+///     .build())
+///     .execute(client)
+///     .await?;
+/// let new_field = &template["SomeNewField"];
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raw<E>(pub E);
+
+impl<E> Endpoint for Raw<E>
+where
+    E: Endpoint,
+{
+    type Request = E::Request;
+    type Response = Value;
+    type Scope = E::Scope;
+
+    fn endpoint(&self) -> Cow<'static, str> {
+        self.0.endpoint()
+    }
+
+    fn path_template(&self) -> Cow<'static, str> {
+        self.0.path_template()
+    }
+
+    fn message_stream(&self) -> Option<Cow<'_, str>> {
+        self.0.message_stream()
+    }
+
+    fn body(&self) -> &Self::Request {
+        self.0.body()
+    }
+
+    fn method(&self) -> http::Method {
+        self.0.method()
+    }
+}
+
+/// An endpoint this crate does not provide yet, described by its method,
+/// path, query and JSON body.
+///
+/// It is sent like any other endpoint, with the client's token and base
+/// URL, and its response is returned as a [`serde_json::Value`]. It uses the
+/// server token unless [`RawEndpoint::with_scope`] says otherwise.
+///
+/// ```
+/// # use postmark::reqwest::PostmarkClient;
+/// use postmark::{AccountToken, Query, RawEndpoint};
+/// use serde_json::json;
+///
+/// # async fn f(client: &PostmarkClient) -> Result<(), Box<dyn std::error::Error>> {
+/// let messages = RawEndpoint::new(http::Method::GET, "/messages/outbound")
+///     .query("count", "10")
+///     .query("offset", "0")
+///     .execute(client)
+///     .await?;
+///
+/// let server = RawEndpoint::new(http::Method::POST, "/servers")
+///     .json(json!({ "Name": "Staging" }))
+///     .with_scope::<AccountToken>()
+///     .execute(client)
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawEndpoint<S = ServerToken> {
+    pub method: http::Method,
+    /// Path relative to the client's base URL, such as `/messages/outbound`.
+    pub path: String,
+    /// Query string parameters, encoded when the request is sent.
+    pub query: Vec<(String, String)>,
+    /// JSON body, not sent for `GET`, `DELETE` and `HEAD` requests.
+    pub body: Value,
+    scope: PhantomData<S>,
+}
+
+impl RawEndpoint {
+    /// A request without query parameters and with an empty JSON object as
+    /// body.
+    pub fn new(method: http::Method, path: impl Into<String>) -> Self {
+        Self {
+            method,
+            path: path.into(),
+            query: Vec::new(),
+            body: Value::Object(Default::default()),
+            scope: PhantomData,
+        }
+    }
+}
+
+impl<S> RawEndpoint<S>
+where
+    S: TokenScope,
+{
+    /// Append a query string parameter.
+    pub fn query(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.query.push((key.into(), value.into()));
+        self
+    }
+
+    /// Set the JSON body.
+    pub fn json(mut self, body: Value) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Authenticate with the token of `T`, [`ServerToken`] or
+    /// [`AccountToken`](crate::AccountToken).
+    pub fn with_scope<T: TokenScope>(self) -> RawEndpoint<T> {
+        RawEndpoint {
+            method: self.method,
+            path: self.path,
+            query: self.query,
+            body: self.body,
+            scope: PhantomData,
+        }
+    }
+}
+
+impl<S> Endpoint for RawEndpoint<S>
+where
+    S: TokenScope,
+{
+    type Request = Value;
+    type Response = Value;
+    type Scope = S;
+
+    fn endpoint(&self) -> Cow<'static, str> {
+        let mut serializer = Serializer::new(String::new());
+        for (key, value) in &self.query {
+            serializer.append_pair(key, value);
+        }
+        endpoint_with_query(&self.path, serializer.finish())
+    }
+
+    fn body(&self) -> &Self::Request {
+        &self.body
+    }
+
+    fn method(&self) -> http::Method {
+        self.method.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use httptest::matchers::{all_of, contains, eq, json_decoded, key, not, request, url_decoded};
+    use httptest::{Expectation, Server, responders::*};
+    use serde_json::json;
+
+    use super::*;
+    use crate::AccountToken;
+    use crate::Query;
+    use crate::api::templates::ListTemplatesRequest;
+    use crate::reqwest::PostmarkClient;
+
+    fn client(server: &Server) -> PostmarkClient {
+        PostmarkClient::builder()
+            .base_url(server.url("/").to_string())
+            .server_token("server-token")
+            .account_token("account-token")
+            .build()
+    }
+
+    #[tokio::test]
+    async fn raw_keeps_fields_the_typed_response_drops() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/templates")).respond_with(
+                json_encoded(json!({
+                    "TotalCount": 0,
+                    "Templates": [],
+                    "NewField": "kept",
+                })),
+            ),
+        );
+
+        let rsp = Raw(ListTemplatesRequest::builder().build())
+            .execute(&client(&server))
+            .await
+            .expect("templates");
+
+        assert_eq!(rsp["NewField"], "kept");
+    }
+
+    #[tokio::test]
+    async fn raw_endpoint_sends_query_body_and_scoped_token() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("POST", "/servers"),
+                request::query(url_decoded(contains(("dry", "true")))),
+                request::headers(contains(("x-postmark-account-token", "account-token"))),
+                request::headers(not(contains(key("x-postmark-server-token")))),
+                request::body(json_decoded(eq(json!({ "Name": "Staging" })))),
+            ])
+            .respond_with(json_encoded(json!({ "ID": 1 }))),
+        );
+
+        let rsp = RawEndpoint::new(http::Method::POST, "/servers")
+            .query("dry", "true")
+            .json(json!({ "Name": "Staging" }))
+            .with_scope::<AccountToken>()
+            .execute(&client(&server))
+            .await
+            .expect("server");
+
+        assert_eq!(rsp, json!({ "ID": 1 }));
+    }
+}
@@ -1,7 +1,9 @@
 use std::convert::TryInto;
+use std::time::Duration;
 
+use crate::transport::prepare_request;
 use crate::{Client, POSTMARK_API_URL};
-use crate::{Endpoint, Query, QueryError};
+use crate::{Endpoint, Query, QueryError, RequestTimeout, ScopedClient};
 use async_trait::async_trait;
 use bytes::Bytes;
 use http::{Request, Response};
@@ -27,6 +29,27 @@ use typed_builder::TypedBuilder;
 ///   .build();
 /// ```
 ///
+/// Tuning the HTTP transport, with a [`reqwest::Client`] built from
+/// [`PostmarkClient::http_client_builder`]:
+/// ```
+/// # use postmark::reqwest::PostmarkClient;
+/// # use std::time::Duration;
+/// # fn main() -> Result<(), reqwest::Error> {
+/// let http_client = PostmarkClient::http_client_builder()
+///   .connect_timeout(Duration::from_secs(2))
+///   .proxy(reqwest::Proxy::https("http://proxy.internal:3128")?)
+///   .pool_max_idle_per_host(16)
+///   .build()?;
+///
+/// let client = PostmarkClient::builder()
+///   .server_token("<sometoken>")
+///   .timeout(Duration::from_secs(10))
+///   .client(http_client)
+///   .build();
+/// # Ok(())
+/// # }
+/// ```
+///
 /// ```compile_fail
 /// use postmark::reqwest::PostmarkClient;
 ///
@@ -42,16 +65,32 @@ pub struct PostmarkClient {
     pub account_token: Option<String>,
     #[builder(default=POSTMARK_API_URL.into(), setter(into))]
     pub base_url: String,
-    #[builder(default=::reqwest::Client::new(), setter(skip))]
+    /// Time allowed for each request, from connecting to reading the whole
+    /// response. A [`RequestTimeout`] on a request takes precedence.
+    #[builder(default, setter(strip_option))]
+    pub timeout: Option<Duration>,
+    /// The `reqwest` client requests are sent with. Defaults to one built
+    /// from [`PostmarkClient::http_client_builder`].
+    #[builder(default = default_http_client())]
     client: reqwest::Client,
 }
 
+/// The `User-Agent` sent by the `reqwest` based clients.
+pub const USER_AGENT: &str = concat!("postmark-rs/", env!("CARGO_PKG_VERSION"));
+
+fn default_http_client() -> reqwest::Client {
+    PostmarkClient::http_client_builder()
+        .build()
+        .expect("default reqwest client builds")
+}
+
 impl std::fmt::Debug for PostmarkClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let Self {
             server_token: ref _server_token,
             account_token: ref _account_token,
             base_url: ref _base_url,
+            timeout: ref _timeout,
             client: ref _client,
         } = *self;
 
@@ -59,6 +98,7 @@ impl std::fmt::Debug for PostmarkClient {
         builder.field("server_token", &_server_token.as_ref().map(|_| "***"));
         builder.field("account_token", &_account_token.as_ref().map(|_| "***"));
         builder.field("base_url", _base_url);
+        builder.field("timeout", _timeout);
         builder.finish()
     }
 }
@@ -69,7 +109,8 @@ impl Default for PostmarkClient {
             base_url: POSTMARK_API_URL.into(),
             server_token: None,
             account_token: None,
-            client: reqwest::Client::new(),
+            timeout: None,
+            client: default_http_client(),
         }
     }
 }
@@ -108,28 +149,22 @@ impl Client for PostmarkClient {
     type Error = PostmarkClientError;
 
     async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
-        let mut req = req;
+        let req = prepare_request::<Self::Error>(
+            req,
+            self.server_token.as_deref(),
+            self.account_token.as_deref(),
+            &self.base_url,
+        )?;
 
-        if let Some(tok) = &self.server_token {
-            req.headers_mut()
-                .append("X-Postmark-Server-Token", tok.try_into()?);
-        }
-
-        if let Some(tok) = &self.account_token {
-            req.headers_mut()
-                .append("X-Postmark-Account-Token", tok.try_into()?);
-        }
-
-        let base_url: url::Url = self.base_url.parse()?;
-
-        let url = match req.uri().path_and_query() {
-            Some(path) => base_url.join(path.as_str())?,
-            None => base_url,
+        let timeout = match req.extensions().get::<RequestTimeout>() {
+            Some(RequestTimeout(timeout)) => Some(*timeout),
+            None => self.timeout,
         };
 
-        *req.uri_mut() = url.as_str().parse()?;
-
-        let reqwest_req: reqwest::Request = req.try_into()?;
+        let mut reqwest_req: reqwest::Request = req.try_into()?;
+        if timeout.is_some() {
+            *reqwest_req.timeout_mut() = timeout;
+        }
         let reqwest_rsp = self.client.execute(reqwest_req).await?;
 
         let mut rsp = Response::builder()
@@ -146,6 +181,15 @@ impl Client for PostmarkClient {
 }
 
 impl PostmarkClient {
+    /// A [`reqwest::ClientBuilder`] set up the way the default client is, to
+    /// tune before passing the client to `PostmarkClient::builder().client(..)`.
+    ///
+    /// It sends [`USER_AGENT`], and accepts gzip responses when the `gzip`
+    /// feature is enabled.
+    pub fn http_client_builder() -> reqwest::ClientBuilder {
+        reqwest::Client::builder().user_agent(USER_AGENT)
+    }
+
     pub async fn execute_endpoint<T>(
         &self,
         request: T,
@@ -155,4 +199,118 @@ impl PostmarkClient {
     {
         request.execute(self).await
     }
+
+    /// Execute `request`, failing with a timeout error when it takes longer
+    /// than `timeout`, whatever [`PostmarkClient::timeout`] is.
+    pub async fn execute_endpoint_with_timeout<T>(
+        &self,
+        request: T,
+        timeout: Duration,
+    ) -> Result<T::Response, QueryError<PostmarkClientError>>
+    where
+        T: Endpoint + Send + Sync,
+    {
+        request
+            .execute(&WithTimeout {
+                client: self,
+                timeout,
+            })
+            .await
+    }
+
+    /// Borrow this client to send requests with another server token,
+    /// sharing its connection pool.
+    pub fn with_server_token(&self, server_token: impl Into<String>) -> ScopedClient<'_, Self> {
+        ScopedClient::new(self, server_token)
+    }
+}
+
+/// Sends every request with a [`RequestTimeout`], for
+/// [`PostmarkClient::execute_endpoint_with_timeout`].
+struct WithTimeout<'a> {
+    client: &'a PostmarkClient,
+    timeout: Duration,
+}
+
+#[async_trait]
+impl Client for WithTimeout<'_> {
+    type Error = PostmarkClientError;
+
+    async fn execute(&self, mut req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        req.extensions_mut().insert(RequestTimeout(self.timeout));
+        self.client.execute(req).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use httptest::matchers::{all_of, contains, request};
+    use httptest::{Expectation, Server, responders::*};
+    use serde_json::json;
+
+    use super::*;
+    use crate::api::templates::ListTemplatesRequest;
+
+    fn templates() -> impl Responder {
+        json_encoded(json!({ "TotalCount": 0, "Templates": [] }))
+    }
+
+    #[tokio::test]
+    async fn sends_the_crate_user_agent() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(all_of![
+                request::method_path("GET", "/templates"),
+                request::headers(contains(("user-agent", USER_AGENT))),
+            ])
+            .respond_with(templates()),
+        );
+
+        let client = PostmarkClient::builder()
+            .base_url(server.url("/").to_string())
+            .server_token("server-token")
+            .build();
+
+        client
+            .execute_endpoint(ListTemplatesRequest::builder().build())
+            .await
+            .expect("templates");
+    }
+
+    #[tokio::test]
+    async fn per_call_timeout_overrides_the_client_timeout() {
+        let server = Server::run();
+        server.expect(
+            Expectation::matching(request::method_path("GET", "/templates"))
+                .times(2)
+                .respond_with(delay_and_then(Duration::from_millis(500), templates())),
+        );
+
+        let client = PostmarkClient::builder()
+            .base_url(server.url("/").to_string())
+            .server_token("server-token")
+            .timeout(Duration::from_secs(10))
+            .build();
+
+        let error = client
+            .execute_endpoint_with_timeout(
+                ListTemplatesRequest::builder().build(),
+                Duration::from_millis(50),
+            )
+            .await
+            .expect_err("timed out");
+        match error {
+            QueryError::Client {
+                source: PostmarkClientError::Communication { source },
+            } => assert!(source.is_timeout()),
+            other => panic!("unexpected error: {other:?}"),
+        }
+
+        client
+            .execute_endpoint(ListTemplatesRequest::builder().build())
+            .await
+            .expect("templates within the client timeout");
+    }
 }
@@ -0,0 +1,414 @@
+//! A [`Client`] decorator that re-sends requests on transient failures.
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use postmark::retry::{RetryClient, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! let client = RetryClient::with_policy(
+//!     PostmarkClient::default(),
+//!     RetryPolicy::builder()
+//!         .max_retries(5)
+//!         .initial_backoff(Duration::from_millis(100))
+//!         .build(),
+//! );
+//! ```
+//!
+//! Keep in mind that Postmark has no idempotency key on its send endpoints:
+//! a send that timed out on the way back may have been accepted, and
+//! retrying it can deliver the email twice.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Request, Response};
+use typed_builder::TypedBuilder;
+
+use crate::Client;
+use crate::client::{is_circuit_open, is_retryable_status, retry_after};
+
+/// Exponential backoff settings used by [`RetryClient`].
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct RetryPolicy {
+    /// How many times a request is sent again after the first attempt.
+    #[builder(default = 3)]
+    pub max_retries: u32,
+    /// Delay before the first retry.
+    #[builder(default = Duration::from_millis(200))]
+    pub initial_backoff: Duration,
+    /// Upper bound for the computed backoff.
+    #[builder(default = Duration::from_secs(10))]
+    pub max_backoff: Duration,
+    /// Factor applied to the backoff after every attempt.
+    ///
+    /// # Panics
+    ///
+    /// The builder panics when the factor is negative or not finite.
+    #[builder(
+        default = 2.0,
+        setter(transform = |multiplier: f64| {
+            assert!(
+                multiplier.is_finite() && multiplier >= 0.0,
+                "the backoff multiplier must be finite and non-negative, got {multiplier}"
+            );
+            multiplier
+        })
+    )]
+    pub multiplier: f64,
+    /// Pick a random delay between zero and the computed backoff
+    /// ("full jitter") so concurrent callers do not retry in lockstep.
+    #[builder(default = true)]
+    pub jitter: bool,
+    /// Wait for the delay given by a `Retry-After` response header, capped at
+    /// `max_backoff`, instead of the computed backoff when one is present.
+    #[builder(default = true)]
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl RetryPolicy {
+    /// The delay to wait before retry number `attempt` (starting at 0).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = i32::try_from(attempt).unwrap_or(i32::MAX);
+        let factor = self.multiplier.powi(exponent);
+        // Out of range factors, such as a negative `multiplier` set on the
+        // struct directly, fall back to `max_backoff` instead of panicking.
+        let backoff = Duration::try_from_secs_f64(self.initial_backoff.as_secs_f64() * factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            backoff.mul_f64(fastrand::f64())
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Wraps a [`Client`] and sends a request again when the inner client fails
+/// or Postmark answers with `429`, `500`, `502`, `503` or `504`.
+///
+/// Failures [`QueryError::is_retryable`](crate::QueryError::is_retryable)
+/// rejects, such as an open circuit breaker, are returned right away.
+///
+/// Every other response is returned untouched, so non-retryable API errors
+/// still surface as [`QueryError::Api`](crate::QueryError::Api). Once the
+/// retries are exhausted the last response or error is returned.
+#[derive(Debug, Clone)]
+pub struct RetryClient<C> {
+    inner: C,
+    policy: RetryPolicy,
+}
+
+impl<C> RetryClient<C> {
+    /// Wrap `inner` using the default [`RetryPolicy`].
+    pub fn new(inner: C) -> Self {
+        Self::with_policy(inner, RetryPolicy::default())
+    }
+
+    /// Wrap `inner` using the given [`RetryPolicy`].
+    pub fn with_policy(inner: C, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// The wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// The policy used to space out retries.
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+}
+
+#[async_trait]
+impl<C> Client for RetryClient<C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = C::Error;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let mut attempt = 0;
+
+        loop {
+            let result = self.inner.execute(req.clone()).await;
+            if attempt >= self.policy.max_retries {
+                return result;
+            }
+
+            let delay = match &result {
+                Ok(rsp) if is_retryable_status(rsp.status()) => self
+                    .policy
+                    .respect_retry_after
+                    .then(|| retry_after(rsp.headers()))
+                    .flatten()
+                    .map(|delay| delay.min(self.policy.max_backoff))
+                    .unwrap_or_else(|| self.policy.backoff(attempt)),
+                Err(error) if !is_circuit_open(error) => self.policy.backoff(attempt),
+                _ => return result,
+            };
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ServerToken;
+    use crate::circuit_breaker::{CircuitBreakerError, CircuitOpen};
+    use http::header::RETRY_AFTER;
+    use http::{HeaderMap, StatusCode};
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::{Endpoint, Query, QueryError};
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("connection refused")]
+    struct TestClientError;
+
+    /// Replays the scripted outcomes in order, then keeps answering `200`.
+    struct ScriptedClient {
+        outcomes: Mutex<Vec<Result<StatusCode, TestClientError>>>,
+        calls: AtomicUsize,
+    }
+
+    impl ScriptedClient {
+        fn new(outcomes: Vec<Result<StatusCode, TestClientError>>) -> Self {
+            Self {
+                outcomes: Mutex::new(outcomes),
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl Client for ScriptedClient {
+        type Error = TestClientError;
+
+        async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(req.body(), &Bytes::from_static(b"{}"));
+
+            let mut outcomes = self.outcomes.lock().expect("lock");
+            let outcome = if outcomes.is_empty() {
+                Ok(StatusCode::OK)
+            } else {
+                outcomes.remove(0)
+            };
+
+            outcome.map(|status| {
+                Response::builder()
+                    .status(status)
+                    .header(RETRY_AFTER, "0")
+                    .body(Bytes::from_static(br#"{"ErrorCode":0,"Message":"OK"}"#))
+                    .expect("response")
+            })
+        }
+    }
+
+    #[derive(serde::Serialize)]
+    struct Empty {}
+
+    #[derive(Debug, serde::Deserialize)]
+    struct EmptyResponse {}
+
+    struct PostEndpoint;
+    impl Endpoint for PostEndpoint {
+        type Request = Empty;
+        type Response = EmptyResponse;
+        type Scope = ServerToken;
+
+        fn endpoint(&self) -> std::borrow::Cow<'static, str> {
+            "/email".into()
+        }
+
+        fn body(&self) -> &Self::Request {
+            &Empty {}
+        }
+    }
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy::builder()
+            .max_retries(max_retries)
+            .initial_backoff(Duration::from_millis(1))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn retries_transient_statuses_and_client_errors() {
+        let client = RetryClient::with_policy(
+            ScriptedClient::new(vec![
+                Ok(StatusCode::TOO_MANY_REQUESTS),
+                Err(TestClientError),
+                Ok(StatusCode::SERVICE_UNAVAILABLE),
+            ]),
+            fast_policy(3),
+        );
+
+        PostEndpoint.execute(&client).await.expect("success");
+        assert_eq!(client.inner().calls(), 4);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_client_side_api_errors() {
+        let client = RetryClient::with_policy(
+            ScriptedClient::new(vec![Ok(StatusCode::UNPROCESSABLE_ENTITY)]),
+            fast_policy(3),
+        );
+
+        let error = PostEndpoint.execute(&client).await.expect_err("api error");
+        assert!(
+            matches!(error, QueryError::Api(error) if error.status == StatusCode::UNPROCESSABLE_ENTITY)
+        );
+        assert_eq!(client.inner().calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn returns_last_error_once_retries_are_exhausted() {
+        let client = RetryClient::with_policy(
+            ScriptedClient::new(vec![
+                Ok(StatusCode::BAD_GATEWAY),
+                Ok(StatusCode::BAD_GATEWAY),
+                Ok(StatusCode::BAD_GATEWAY),
+            ]),
+            fast_policy(2),
+        );
+
+        let error = PostEndpoint.execute(&client).await.expect_err("api error");
+        assert!(error.is_retryable());
+        assert_eq!(client.inner().calls(), 3);
+    }
+
+    /// Fails every request like a wrapped open circuit breaker.
+    #[derive(Default)]
+    struct OpenCircuitClient {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Client for OpenCircuitClient {
+        type Error = CircuitBreakerError<TestClientError>;
+
+        async fn execute(&self, _req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Err(CircuitBreakerError::Open {
+                source: CircuitOpen,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_an_open_circuit() {
+        let client = RetryClient::with_policy(OpenCircuitClient::default(), fast_policy(3));
+
+        let error = PostEndpoint.execute(&client).await.expect_err("open");
+        assert!(matches!(error, QueryError::CircuitOpen));
+        assert_eq!(client.inner().calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retry_after_is_capped_at_max_backoff() {
+        struct SlowDownClient(AtomicUsize);
+
+        #[async_trait]
+        impl Client for SlowDownClient {
+            type Error = TestClientError;
+
+            async fn execute(&self, _req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+                let status = match self.0.fetch_add(1, Ordering::SeqCst) {
+                    0 => StatusCode::TOO_MANY_REQUESTS,
+                    _ => StatusCode::OK,
+                };
+                Ok(Response::builder()
+                    .status(status)
+                    .header(RETRY_AFTER, "3600")
+                    .body(Bytes::from_static(b"{}"))
+                    .expect("response"))
+            }
+        }
+
+        let client = RetryClient::with_policy(
+            SlowDownClient(AtomicUsize::new(0)),
+            RetryPolicy::builder()
+                .max_backoff(Duration::from_secs(2))
+                .build(),
+        );
+
+        let start = tokio::time::Instant::now();
+        PostEndpoint.execute(&client).await.expect("success");
+        assert_eq!(start.elapsed(), Duration::from_secs(2));
+    }
+
+    #[test]
+    #[should_panic(expected = "backoff multiplier")]
+    fn builder_rejects_a_negative_multiplier() {
+        RetryPolicy::builder().multiplier(-1.0).build();
+    }
+
+    #[test]
+    fn backoff_survives_an_invalid_multiplier() {
+        let policy = RetryPolicy {
+            multiplier: f64::NAN,
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(policy.backoff(1), policy.max_backoff);
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_the_cap() {
+        let policy = RetryPolicy::builder()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(350))
+            .jitter(false)
+            .build();
+
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff(2), Duration::from_millis(350));
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn jittered_backoff_stays_below_the_computed_delay() {
+        let policy = RetryPolicy::builder()
+            .initial_backoff(Duration::from_millis(100))
+            .build();
+
+        for _ in 0..100 {
+            assert!(policy.backoff(1) <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(
+            RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+}
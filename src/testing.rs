@@ -0,0 +1,409 @@
+//! Test doubles for code that talks to Postmark through this crate.
+//!
+//! [`MockClient`] is a [`Client`] answering from canned responses queued per
+//! method and path. It records every request so tests can assert on what was
+//! sent, decoded back into the request types:
+//!
+//! ```
+//! use http::Method;
+//! use postmark::Query;
+//! use postmark::api::email::{SendEmailResponse, SendEmailWithTemplateRequest};
+//! use postmark::testing::{MockClient, MockResponse};
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let client = MockClient::new();
+//! client.respond(
+//!     Method::POST,
+//!     "/email/withTemplate",
+//!     MockResponse::ok(SendEmailResponse::default()),
+//! );
+//!
+//! // The code under test.
+//! SendEmailWithTemplateRequest::builder()
+//!     .from("me@example.com")
+//!     .to("you@example.com")
+//!     .template_alias("welcome")
+//!     .build()
+//!     .execute(&client)
+//!     .await
+//!     .unwrap();
+//!
+//! client.assert_sent::<SendEmailWithTemplateRequest, _>(
+//!     Method::POST,
+//!     "/email/withTemplate",
+//!     |req| req.to == "you@example.com" && req.template_alias.as_deref() == Some("welcome"),
+//! );
+//! # }
+//! ```
+//!
+//! [`CassetteClient`] records the exchanges of a real client to a file once,
+//! then replays them offline.
+
+mod cassette;
+
+pub use cassette::*;
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, Request, Response, StatusCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::Client;
+
+/// A canned response served by [`MockClient`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+impl MockResponse {
+    /// A response with the given status and JSON body.
+    pub fn json<T: Serialize>(status: StatusCode, body: T) -> Self {
+        Self {
+            status,
+            headers: HeaderMap::new(),
+            body: serde_json::to_vec(&body)
+                .expect("mock response body serializes to JSON")
+                .into(),
+        }
+    }
+
+    /// Add a response header.
+    ///
+    /// # Panics
+    ///
+    /// Panics when `name` or `value` is not a valid header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.append(
+            HeaderName::from_bytes(name.as_bytes()).expect("mock header name is valid"),
+            HeaderValue::from_str(value).expect("mock header value is valid"),
+        );
+        self
+    }
+
+    /// A `200 OK` response with the given JSON body.
+    pub fn ok<T: Serialize>(body: T) -> Self {
+        Self::json(StatusCode::OK, body)
+    }
+
+    /// An error response shaped like Postmark's, which surfaces as
+    /// [`QueryError::Api`](crate::QueryError::Api).
+    pub fn api_error(status: StatusCode, error_code: i64, message: impl Into<String>) -> Self {
+        Self::json(
+            status,
+            serde_json::json!({
+                "ErrorCode": error_code,
+                "Message": message.into(),
+            }),
+        )
+    }
+
+    /// A `422` with error code `406`, returned when sending to a recipient
+    /// marked as inactive.
+    pub fn inactive_recipient(email: &str) -> Self {
+        Self::api_error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            406,
+            format!(
+                "You tried to send to recipient(s) that have been marked as inactive. Found inactive addresses: {email}."
+            ),
+        )
+    }
+
+    /// A `422` with the given Postmark error code, as returned for invalid
+    /// requests.
+    pub fn unprocessable(error_code: i64, message: impl Into<String>) -> Self {
+        Self::api_error(StatusCode::UNPROCESSABLE_ENTITY, error_code, message)
+    }
+
+    /// A `500` as returned when Postmark itself fails.
+    pub fn server_error() -> Self {
+        Self::api_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            0,
+            "Internal server error",
+        )
+    }
+}
+
+/// The error returned by [`MockClient`] for requests it has no response for.
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("no mock response queued for {method} {path}")]
+pub struct MockClientError {
+    pub method: Method,
+    pub path: String,
+}
+
+#[derive(Default)]
+struct MockState {
+    responses: HashMap<(Method, String), VecDeque<MockResponse>>,
+    requests: Vec<Request<Bytes>>,
+}
+
+/// A [`Client`] answering requests with queued [`MockResponse`]s and
+/// recording every request it receives.
+///
+/// Responses are matched on method and path (the query string is ignored)
+/// and served in the order they were queued. The last response queued for a
+/// method and path keeps being served once the others are used up. Requests
+/// without a matching response fail with a [`MockClientError`].
+///
+/// Clones share their responses and recorded requests.
+#[derive(Clone, Default)]
+pub struct MockClient {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl fmt::Debug for MockClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = self.lock();
+        f.debug_struct("MockClient")
+            .field("queued", &state.responses.keys().collect::<Vec<_>>())
+            .field("requests", &state.requests.len())
+            .finish()
+    }
+}
+
+impl MockClient {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().expect("mock client state poisoned")
+    }
+
+    /// Queue `response` for the next request to `method` `path`.
+    pub fn respond(&self, method: Method, path: &str, response: MockResponse) -> &Self {
+        self.lock()
+            .responses
+            .entry((method, path.to_string()))
+            .or_default()
+            .push_back(response);
+        self
+    }
+
+    /// Every request received so far, in order.
+    pub fn requests(&self) -> Vec<Request<Bytes>> {
+        self.lock().requests.clone()
+    }
+
+    /// The requests received for `method` `path`, in order.
+    pub fn requests_to(&self, method: Method, path: &str) -> Vec<Request<Bytes>> {
+        self.lock()
+            .requests
+            .iter()
+            .filter(|req| req.method() == method && req.uri().path() == path)
+            .cloned()
+            .collect()
+    }
+
+    /// The bodies sent to `method` `path`, decoded as `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a recorded body does not decode as `T`.
+    pub fn sent<T: DeserializeOwned>(&self, method: Method, path: &str) -> Vec<T> {
+        self.requests_to(method.clone(), path)
+            .iter()
+            .map(|req| {
+                serde_json::from_slice(req.body()).unwrap_or_else(|err| {
+                    panic!(
+                        "body sent to {method} {path} is not a {}: {err}",
+                        std::any::type_name::<T>()
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Assert that at least one body sent to `method` `path` decodes as `T`
+    /// and matches `predicate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics, listing the recorded bodies, when none matches.
+    pub fn assert_sent<T, F>(&self, method: Method, path: &str, predicate: F)
+    where
+        T: DeserializeOwned + fmt::Debug,
+        F: Fn(&T) -> bool,
+    {
+        let sent = self.sent::<T>(method.clone(), path);
+        assert!(
+            sent.iter().any(predicate),
+            "no {} sent to {method} {path} matched, sent: {sent:#?}",
+            std::any::type_name::<T>()
+        );
+    }
+
+    /// Assert that nothing was sent to `method` `path`.
+    pub fn assert_not_sent(&self, method: Method, path: &str) {
+        let sent = self.requests_to(method.clone(), path);
+        assert!(
+            sent.is_empty(),
+            "expected no request to {method} {path}, got {}",
+            sent.len()
+        );
+    }
+}
+
+#[async_trait]
+impl Client for MockClient {
+    type Error = MockClientError;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let mut state = self.lock();
+        let key = (req.method().clone(), req.uri().path().to_string());
+        state.requests.push(req);
+
+        let response = match state.responses.get_mut(&key) {
+            Some(queue) if queue.len() > 1 => queue.pop_front(),
+            Some(queue) => queue.front().cloned(),
+            None => None,
+        };
+
+        let response = response.ok_or(MockClientError {
+            method: key.0,
+            path: key.1,
+        })?;
+
+        let mut rsp = Response::builder()
+            .status(response.status)
+            .header("Content-Type", "application/json")
+            .body(response.body)
+            .expect("mock response is a valid http response");
+        rsp.headers_mut().extend(response.headers);
+        Ok(rsp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::api::Body;
+    use crate::api::email::{SendEmailRequest, SendEmailResponse};
+    use crate::api::templates::ListTemplatesRequest;
+    use crate::{PostmarkErrorCode, Query, QueryError};
+
+    fn email(to: &str) -> SendEmailRequest {
+        SendEmailRequest::builder()
+            .from("pa@example.com")
+            .to(to)
+            .body(Body::text("hello".into()))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn serves_queued_responses_in_order_and_repeats_the_last() {
+        let client = MockClient::new();
+        client
+            .respond(
+                Method::POST,
+                "/email",
+                MockResponse::ok(SendEmailResponse {
+                    message_id: Some("first".into()),
+                    ..Default::default()
+                }),
+            )
+            .respond(
+                Method::POST,
+                "/email",
+                MockResponse::ok(SendEmailResponse {
+                    message_id: Some("second".into()),
+                    ..Default::default()
+                }),
+            );
+
+        let mut ids = Vec::new();
+        for _ in 0..3 {
+            let resp = email("a@example.com").execute(&client).await.expect("ok");
+            ids.push(resp.message_id.expect("message id"));
+        }
+
+        assert_eq!(ids, ["first", "second", "second"]);
+        assert_eq!(client.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn simulated_api_errors_parse_like_postmark_errors() {
+        let client = MockClient::new();
+        client.respond(
+            Method::POST,
+            "/email",
+            MockResponse::inactive_recipient("a@example.com"),
+        );
+
+        let error = email("a@example.com")
+            .execute(&client)
+            .await
+            .expect_err("api error");
+
+        assert!(matches!(
+            error,
+            QueryError::Api(error)
+                if error.status == StatusCode::UNPROCESSABLE_ENTITY
+                    && error.error_code == Some(PostmarkErrorCode::InactiveRecipient)
+        ));
+
+        let client = MockClient::new();
+        client.respond(Method::POST, "/email", MockResponse::server_error());
+        let error = email("a@example.com")
+            .execute(&client)
+            .await
+            .expect_err("api error");
+        assert!(error.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn unmatched_requests_fail_and_are_recorded() {
+        let client = MockClient::new();
+
+        let error = ListTemplatesRequest::builder()
+            .build()
+            .execute(&client)
+            .await
+            .expect_err("no response");
+
+        assert!(matches!(
+            error,
+            QueryError::Client { source } if source == MockClientError {
+                method: Method::GET,
+                path: "/templates".into(),
+            }
+        ));
+        assert_eq!(client.requests_to(Method::GET, "/templates").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn typed_assertions_decode_recorded_bodies() {
+        let client = MockClient::new();
+        client.respond(Method::POST, "/email", MockResponse::ok(json!({})));
+
+        let _ = email("a@example.com").execute(&client).await;
+        let _ = email("b@example.com").execute(&client).await;
+
+        let sent = client.sent::<SendEmailRequest>(Method::POST, "/email");
+        assert_eq!(sent, vec![email("a@example.com"), email("b@example.com")]);
+        client.assert_sent::<SendEmailRequest, _>(Method::POST, "/email", |req| {
+            req.to == "b@example.com"
+        });
+        client.assert_not_sent(Method::POST, "/email/batch");
+    }
+
+    #[test]
+    #[should_panic(expected = "no postmark::api::email::send_email::SendEmailRequest sent")]
+    fn assert_sent_panics_without_a_match() {
+        MockClient::new().assert_sent::<SendEmailRequest, _>(Method::POST, "/email", |_| true);
+    }
+}
@@ -0,0 +1,569 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use bytes::Bytes;
+use http::{HeaderMap, Request, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::Client;
+
+/// Headers whose values are replaced by [`REDACTED`] before a cassette is
+/// written.
+pub const REDACTED_HEADERS: &[&str] = &["x-postmark-server-token", "x-postmark-account-token"];
+
+/// The value stored in place of redacted header values.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Whether a [`CassetteClient`] talks to the inner client or to its file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CassetteMode {
+    /// Send every request through the inner client and append the exchange
+    /// to the cassette, replacing what the file held before.
+    Record,
+    /// Answer from the cassette only, failing on requests it does not hold.
+    Replay,
+}
+
+/// One recorded request and the response it got.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub uri: String,
+    pub headers: Vec<(String, String)>,
+    pub body: RecordedBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: RecordedBody,
+}
+
+/// A recorded body, kept as text when it is UTF-8 and base64 encoded
+/// otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecordedBody {
+    Text(String),
+    Base64 { base64: String },
+}
+
+impl RecordedBody {
+    fn new(body: &[u8]) -> Self {
+        match std::str::from_utf8(body) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Base64 {
+                base64: BASE64.encode(body),
+            },
+        }
+    }
+
+    /// The bytes of the body.
+    pub fn to_bytes(&self) -> Result<Bytes, base64::DecodeError> {
+        match self {
+            Self::Text(text) => Ok(Bytes::from(text.clone())),
+            Self::Base64 { base64 } => BASE64.decode(base64).map(Bytes::from),
+        }
+    }
+}
+
+type BodyRedactor = Box<dyn Fn(&mut Value) + Send + Sync>;
+
+/// The JSON document a [`CassetteClient`] reads and writes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Error, Debug)]
+pub enum CassetteError<E>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    #[error("inner client error: {}", source)]
+    Inner { source: E },
+    #[error("no recorded interaction left for {method} {uri}")]
+    Unmatched { method: String, uri: String },
+    #[error("cassette i/o error: {}", source)]
+    Io {
+        #[from]
+        source: std::io::Error,
+    },
+    #[error("invalid cassette: {}", source)]
+    Json {
+        #[from]
+        source: serde_json::Error,
+    },
+    #[error("invalid recorded response: {}", source)]
+    Http {
+        #[from]
+        source: http::Error,
+    },
+    #[error("invalid recorded body: {}", source)]
+    Base64 {
+        #[from]
+        source: base64::DecodeError,
+    },
+}
+
+struct CassetteState {
+    cassette: Cassette,
+    used: Vec<bool>,
+    /// Whether interactions were recorded since the file was last written.
+    unsaved: bool,
+}
+
+/// A [`Client`] that records the exchanges of an inner client to a JSON file,
+/// then replays them without touching the network.
+///
+/// [`CassetteClient::new`] replays when the file exists and records
+/// otherwise, so the first run against a sandbox server writes the cassette
+/// and every later run is hermetic. Token headers listed in
+/// [`REDACTED_HEADERS`] are redacted before anything is written, and
+/// [`CassetteClient::with_body_redactor`] redacts JSON bodies.
+///
+/// Recorded interactions are kept in memory and written when the client is
+/// dropped, or by [`CassetteClient::save`], so requests never wait on the
+/// file system.
+///
+/// During replay, a request matches an interaction with the same method, URI
+/// and body. Interactions are used once each, in the order they were
+/// recorded, so repeated identical requests replay in sequence.
+///
+/// ```no_run
+/// # use postmark::reqwest::PostmarkClient;
+/// use postmark::testing::CassetteClient;
+///
+/// let inner = PostmarkClient::builder()
+///     .server_token(std::env::var("POSTMARK_API_TOKEN").unwrap_or_default())
+///     .build();
+/// let client = CassetteClient::new("tests/cassettes/send_email.json", inner).unwrap();
+/// ```
+pub struct CassetteClient<C> {
+    inner: C,
+    path: PathBuf,
+    mode: CassetteMode,
+    state: Mutex<CassetteState>,
+    redact_body: Option<BodyRedactor>,
+}
+
+impl<C> std::fmt::Debug for CassetteClient<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CassetteClient")
+            .field("path", &self.path)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl<C> CassetteClient<C>
+where
+    C: Client,
+{
+    /// Replay `path` when it exists, record through `inner` otherwise.
+    pub fn new(path: impl AsRef<Path>, inner: C) -> Result<Self, CassetteError<C::Error>> {
+        let mode = if path.as_ref().exists() {
+            CassetteMode::Replay
+        } else {
+            CassetteMode::Record
+        };
+        Self::with_mode(path, inner, mode)
+    }
+
+    /// Use `path` in the given mode.
+    pub fn with_mode(
+        path: impl AsRef<Path>,
+        inner: C,
+        mode: CassetteMode,
+    ) -> Result<Self, CassetteError<C::Error>> {
+        let path = path.as_ref().to_path_buf();
+        let cassette = match mode {
+            CassetteMode::Record => Cassette::default(),
+            CassetteMode::Replay => serde_json::from_slice(&std::fs::read(&path)?)?,
+        };
+
+        Ok(Self {
+            inner,
+            path,
+            mode,
+            state: Mutex::new(CassetteState {
+                used: vec![false; cassette.interactions.len()],
+                cassette,
+                unsaved: false,
+            }),
+            redact_body: None,
+        })
+    }
+
+    /// Call `redact` on every JSON request and response body before it is
+    /// recorded, such as to blank out recipients or message contents.
+    ///
+    /// Requests are redacted the same way before they are matched during
+    /// replay, and replayed responses carry the redacted bodies.
+    ///
+    /// ```no_run
+    /// # use postmark::reqwest::PostmarkClient;
+    /// use postmark::testing::{CassetteClient, REDACTED};
+    ///
+    /// let client = CassetteClient::new("tests/cassettes/send_email.json", PostmarkClient::default())
+    ///     .unwrap()
+    ///     .with_body_redactor(|body| {
+    ///         if let Some(to) = body.get_mut("To") {
+    ///             *to = REDACTED.into();
+    ///         }
+    ///     });
+    /// ```
+    pub fn with_body_redactor(
+        mut self,
+        redact: impl Fn(&mut Value) + Send + Sync + 'static,
+    ) -> Self {
+        self.redact_body = Some(Box::new(redact));
+        self
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    /// Write the interactions recorded so far to the cassette file.
+    pub fn save(&self) -> Result<(), CassetteError<C::Error>> {
+        Ok(self.write()?)
+    }
+
+    /// The interactions recorded or loaded so far.
+    pub fn interactions(&self) -> Vec<Interaction> {
+        self.lock().cassette.interactions.clone()
+    }
+
+    fn replay(&self, req: &RecordedRequest) -> Result<Response<Bytes>, CassetteError<C::Error>> {
+        let mut state = self.lock();
+        let CassetteState { cassette, used, .. } = &mut *state;
+
+        let index = cassette
+            .interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| {
+                !used
+                    && interaction.request.method == req.method
+                    && interaction.request.uri == req.uri
+                    && interaction.request.body == req.body
+            })
+            .ok_or_else(|| CassetteError::Unmatched {
+                method: req.method.clone(),
+                uri: req.uri.clone(),
+            })?;
+        used[index] = true;
+
+        let recorded = &cassette.interactions[index].response;
+        let mut rsp = Response::builder()
+            .status(StatusCode::from_u16(recorded.status).map_err(http::Error::from)?);
+        for (name, value) in &recorded.headers {
+            rsp = rsp.header(name, value);
+        }
+        Ok(rsp.body(recorded.body.to_bytes()?)?)
+    }
+
+    fn record(&self, interaction: Interaction) {
+        let mut state = self.lock();
+        state.cassette.interactions.push(interaction);
+        state.used.push(true);
+        state.unsaved = true;
+    }
+}
+
+impl<C> CassetteClient<C> {
+    fn lock(&self) -> std::sync::MutexGuard<'_, CassetteState> {
+        self.state.lock().expect("cassette state poisoned")
+    }
+
+    fn body(&self, body: &[u8]) -> RecordedBody {
+        if let Some(redact) = &self.redact_body
+            && let Ok(mut json) = serde_json::from_slice::<Value>(body)
+        {
+            redact(&mut json);
+            return RecordedBody::Text(json.to_string());
+        }
+        RecordedBody::new(body)
+    }
+
+    fn write(&self) -> std::io::Result<()> {
+        let mut state = self.lock();
+        if !state.unsaved {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_vec_pretty(&state.cassette)?)?;
+        state.unsaved = false;
+        Ok(())
+    }
+}
+
+impl<C> Drop for CassetteClient<C> {
+    fn drop(&mut self) {
+        // Errors can't be reported from here, call `save` to see them.
+        let _ = self.write();
+    }
+}
+
+#[async_trait]
+impl<C> Client for CassetteClient<C>
+where
+    C: Client + Send + Sync,
+{
+    type Error = CassetteError<C::Error>;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        let recorded_req = RecordedRequest {
+            method: req.method().to_string(),
+            uri: req.uri().to_string(),
+            headers: redacted(req.headers()),
+            body: self.body(req.body()),
+        };
+
+        if self.mode == CassetteMode::Replay {
+            return self.replay(&recorded_req);
+        }
+
+        let rsp = self
+            .inner
+            .execute(req)
+            .await
+            .map_err(|source| CassetteError::Inner { source })?;
+
+        self.record(Interaction {
+            request: recorded_req,
+            response: RecordedResponse {
+                status: rsp.status().as_u16(),
+                headers: redacted(rsp.headers()),
+                body: self.body(rsp.body()),
+            },
+        });
+
+        Ok(rsp)
+    }
+}
+
+fn redacted(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                REDACTED.to_string()
+            } else {
+                String::from_utf8_lossy(value.as_bytes()).into_owned()
+            };
+            (name.as_str().to_string(), value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use crate::api::Body;
+    use crate::api::email::{SendEmailRequest, SendEmailResponse};
+    use crate::testing::{MockClient, MockResponse};
+    use crate::{Query, QueryError};
+    use http::Method;
+
+    fn cassette_path(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        std::env::temp_dir().join(format!(
+            "postmark-cassette-{}-{}-{name}.json",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::SeqCst)
+        ))
+    }
+
+    fn email(to: &str) -> SendEmailRequest {
+        SendEmailRequest::builder()
+            .from("pa@example.com")
+            .to(to)
+            .body(Body::text("hello".into()))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn records_then_replays_without_the_inner_client() {
+        let path = cassette_path("record-replay");
+
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email",
+            MockResponse::ok(serde_json::json!({
+                "MessageID": "recorded-id",
+                "ErrorCode": 0,
+                "Message": "OK"
+            })),
+        );
+
+        let recorder = CassetteClient::new(&path, mock.clone()).expect("cassette");
+        assert_eq!(recorder.mode(), CassetteMode::Record);
+        let recorded = email("a@example.com").execute(&recorder).await.expect("ok");
+        drop(recorder);
+
+        let replayer = CassetteClient::new(&path, MockClient::new()).expect("cassette");
+        assert_eq!(replayer.mode(), CassetteMode::Replay);
+        let replayed = email("a@example.com").execute(&replayer).await.expect("ok");
+
+        assert_eq!(recorded, replayed);
+        assert_eq!(replayed.message_id.as_deref(), Some("recorded-id"));
+        assert_eq!(mock.requests().len(), 1);
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn replay_fails_on_unmatched_and_exhausted_requests() {
+        let path = cassette_path("unmatched");
+
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email",
+            MockResponse::ok(SendEmailResponse::default()),
+        );
+        let recorder = CassetteClient::new(&path, mock).expect("cassette");
+        let _ = email("a@example.com").execute(&recorder).await;
+        recorder.save().expect("saved");
+
+        let replayer = CassetteClient::with_mode(&path, MockClient::new(), CassetteMode::Replay)
+            .expect("cassette");
+
+        let error = email("b@example.com")
+            .execute(&replayer)
+            .await
+            .expect_err("unmatched body");
+        assert!(matches!(
+            error,
+            QueryError::Client {
+                source: CassetteError::Unmatched { .. }
+            }
+        ));
+
+        email("a@example.com").execute(&replayer).await.expect("ok");
+        let error = email("a@example.com")
+            .execute(&replayer)
+            .await
+            .expect_err("interaction already used");
+        assert!(matches!(
+            error,
+            QueryError::Client {
+                source: CassetteError::Unmatched { .. }
+            }
+        ));
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn tokens_are_redacted_before_writing() {
+        let path = cassette_path("redaction");
+
+        let mock = MockClient::new();
+        mock.respond(
+            Method::GET,
+            "/server",
+            MockResponse::ok(SendEmailResponse::default()),
+        );
+        let recorder = CassetteClient::new(&path, mock).expect("cassette");
+
+        let req = Request::get("/server")
+            .header("X-Postmark-Server-Token", "server-secret")
+            .header("X-Postmark-Account-Token", "account-secret")
+            .header("Accept", "application/json")
+            .body(Bytes::new())
+            .expect("request");
+        recorder.execute(req).await.expect("ok");
+        recorder.save().expect("saved");
+
+        let written = std::fs::read_to_string(&path).expect("cassette written");
+        assert!(!written.contains("server-secret"));
+        assert!(!written.contains("account-secret"));
+        assert!(
+            recorder.interactions()[0]
+                .request
+                .headers
+                .contains(&("x-postmark-server-token".into(), REDACTED.into()))
+        );
+
+        std::fs::remove_file(path).ok();
+    }
+
+    #[tokio::test]
+    async fn bodies_are_redacted_and_binary_bodies_kept_intact() {
+        let path = cassette_path("body-redaction");
+        let binary = Bytes::from_static(&[0x1f, 0x8b, 0xff, 0x00]);
+
+        let mock = MockClient::new();
+        mock.respond(
+            Method::POST,
+            "/email",
+            MockResponse::ok(SendEmailResponse::default()),
+        )
+        .respond(
+            Method::GET,
+            "/messages/outbound/abc/dump",
+            MockResponse {
+                status: StatusCode::OK,
+                headers: HeaderMap::new(),
+                body: binary.clone(),
+            },
+        );
+        let redact = |body: &mut Value| {
+            if let Some(to) = body.get_mut("To") {
+                *to = REDACTED.into();
+            }
+        };
+
+        let recorder = CassetteClient::new(&path, mock)
+            .expect("cassette")
+            .with_body_redactor(redact);
+        email("secret@example.com")
+            .execute(&recorder)
+            .await
+            .expect("ok");
+        let dump = Request::get("/messages/outbound/abc/dump")
+            .body(Bytes::new())
+            .expect("request");
+        recorder.execute(dump).await.expect("ok");
+        drop(recorder);
+
+        let written = std::fs::read_to_string(&path).expect("cassette written");
+        assert!(!written.contains("secret@example.com"));
+
+        let replayer = CassetteClient::new(&path, MockClient::new())
+            .expect("cassette")
+            .with_body_redactor(redact);
+        email("other@example.com")
+            .execute(&replayer)
+            .await
+            .expect("matches once redacted");
+        let dump = Request::get("/messages/outbound/abc/dump")
+            .body(Bytes::new())
+            .expect("request");
+        let rsp = replayer.execute(dump).await.expect("ok");
+        assert_eq!(rsp.body(), &binary);
+
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -0,0 +1,204 @@
+//! Bridges between [`Client`] and [`tower::Service`](::tower::Service).
+//!
+//! Any `Service<Request<Bytes>, Response = Response<Bytes>>` is a [`Client`],
+//! so endpoints can be executed over a stack of tower middleware. To put
+//! middleware in front of an existing client, wrap it in a [`PostmarkService`]
+//! first:
+//!
+//! ```
+//! # use postmark::reqwest::PostmarkClient;
+//! use postmark::tower::PostmarkService;
+//! use tower::ServiceBuilder;
+//!
+//! let client = ServiceBuilder::new()
+//!     .map_request(|req: http::Request<bytes::Bytes>| req)
+//!     .service(PostmarkService::new(PostmarkClient::default()));
+//!
+//! // `client` can now be passed to `Query::execute`.
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use ::tower::{BoxError, Service, ServiceExt};
+use async_trait::async_trait;
+use bytes::Bytes;
+use http::{Request, Response};
+use thiserror::Error;
+
+use crate::Client;
+
+/// The error of a [`Client`] backed by a tower service.
+///
+/// Middleware commonly fails with a [`BoxError`], which does not implement
+/// [`std::error::Error`] itself, so service errors are boxed into this type.
+#[derive(Error, Debug)]
+#[error("service error: {}", source)]
+pub struct ServiceError {
+    pub source: BoxError,
+}
+
+#[async_trait]
+impl<S> Client for S
+where
+    S: Service<Request<Bytes>, Response = Response<Bytes>> + Clone + Send + Sync,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+{
+    type Error = ServiceError;
+
+    async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+        self.clone()
+            .oneshot(req)
+            .await
+            .map_err(|err| ServiceError { source: err.into() })
+    }
+}
+
+/// Exposes a [`Client`] as a [`tower::Service`](::tower::Service), so it can
+/// be wrapped with tower layers.
+#[derive(Debug)]
+pub struct PostmarkService<C> {
+    client: Arc<C>,
+}
+
+impl<C> PostmarkService<C> {
+    pub fn new(client: C) -> Self {
+        Self {
+            client: Arc::new(client),
+        }
+    }
+
+    /// The wrapped client.
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+}
+
+impl<C> Clone for PostmarkService<C> {
+    fn clone(&self) -> Self {
+        Self {
+            client: self.client.clone(),
+        }
+    }
+}
+
+impl<C> Service<Request<Bytes>> for PostmarkService<C>
+where
+    C: Client + Send + Sync + 'static,
+{
+    type Response = Response<Bytes>;
+    type Error = C::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Bytes>) -> Self::Future {
+        let client = self.client.clone();
+        Box::pin(async move { client.execute(req).await })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ServerToken;
+    use ::tower::ServiceBuilder;
+    use ::tower::service_fn;
+    use http::StatusCode;
+    use std::borrow::Cow;
+
+    use crate::{Endpoint, Query, QueryError};
+
+    #[derive(serde::Serialize)]
+    struct NoBody;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct OkResponse {
+        ok: bool,
+    }
+
+    struct GetEndpoint;
+    impl Endpoint for GetEndpoint {
+        type Request = NoBody;
+        type Response = OkResponse;
+        type Scope = ServerToken;
+
+        fn endpoint(&self) -> Cow<'static, str> {
+            "/test-get".into()
+        }
+
+        fn body(&self) -> &Self::Request {
+            static BODY: NoBody = NoBody;
+            &BODY
+        }
+
+        fn method(&self) -> http::Method {
+            http::Method::GET
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("test client error")]
+    struct TestClientError;
+
+    struct EchoPathClient;
+
+    #[async_trait]
+    impl Client for EchoPathClient {
+        type Error = TestClientError;
+
+        async fn execute(&self, req: Request<Bytes>) -> Result<Response<Bytes>, Self::Error> {
+            let ok = req.headers().contains_key("x-layered");
+            Ok(Response::builder()
+                .status(StatusCode::OK)
+                .body(Bytes::from(format!(r#"{{"ok":{ok}}}"#)))
+                .expect("response"))
+        }
+    }
+
+    #[tokio::test]
+    async fn service_is_a_client() {
+        let service = service_fn(|req: Request<Bytes>| async move {
+            assert_eq!(req.uri().path(), "/test-get");
+            Ok::<_, BoxError>(
+                Response::builder()
+                    .body(Bytes::from_static(br#"{"ok":true}"#))
+                    .expect("response"),
+            )
+        });
+
+        let response = GetEndpoint.execute(&service).await.expect("execute");
+        assert!(response.ok);
+    }
+
+    #[tokio::test]
+    async fn service_errors_are_client_errors() {
+        let service = service_fn(|_req: Request<Bytes>| async move {
+            Err::<Response<Bytes>, BoxError>("overloaded".into())
+        });
+
+        let error = GetEndpoint.execute(&service).await.expect_err("error");
+        assert!(
+            matches!(error, QueryError::Client { source } if source.source.to_string() == "overloaded")
+        );
+    }
+
+    #[tokio::test]
+    async fn layers_apply_to_wrapped_clients() {
+        let stack = ServiceBuilder::new()
+            .map_request(|mut req: Request<Bytes>| {
+                req.headers_mut()
+                    .insert("x-layered", http::HeaderValue::from_static("1"));
+                req
+            })
+            .service(PostmarkService::new(EchoPathClient));
+
+        let response = GetEndpoint.execute(&stack).await.expect("execute");
+        assert!(response.ok);
+    }
+}
@@ -0,0 +1,141 @@
+//! Plumbing shared by the bundled [`Client`](crate::Client) implementations.
+
+use std::convert::TryInto;
+
+use bytes::Bytes;
+use http::Request;
+
+use crate::client::SERVER_TOKEN_HEADER;
+use crate::{EndpointInfo, ServerTokenOverride, TokenKind};
+
+/// Attach the Postmark token headers and resolve the request path against
+/// `base_url`, the way every bundled client sends requests.
+///
+/// Only the token matching the [`EndpointInfo`] scope is attached. Requests
+/// that were not built from an [`Endpoint`](crate::Endpoint) get both. A
+/// [`ServerTokenOverride`] extension replaces `server_token`.
+pub(crate) fn prepare_request<E>(
+    mut req: Request<Bytes>,
+    server_token: Option<&str>,
+    account_token: Option<&str>,
+    base_url: &str,
+) -> Result<Request<Bytes>, E>
+where
+    E: From<http::header::InvalidHeaderValue> + From<url::ParseError> + From<http::uri::InvalidUri>,
+{
+    let server_token_override = req.extensions_mut().remove::<ServerTokenOverride>();
+    let server_token = match &server_token_override {
+        Some(ServerTokenOverride(tok)) => Some(tok.as_str()),
+        None => server_token,
+    };
+
+    let token = req
+        .extensions()
+        .get::<EndpointInfo>()
+        .map(|info| info.token);
+    let (server_token, account_token) = match token {
+        Some(TokenKind::Server) => (server_token, None),
+        Some(TokenKind::Account) => (None, account_token),
+        None => (server_token, account_token),
+    };
+
+    if let Some(tok) = server_token {
+        let tok = tok.try_into()?;
+        // The override already set its header, replace it rather than
+        // sending the token twice.
+        if server_token_override.is_some() {
+            req.headers_mut().insert(SERVER_TOKEN_HEADER, tok);
+        } else {
+            req.headers_mut().append(SERVER_TOKEN_HEADER, tok);
+        }
+    }
+
+    if let Some(tok) = account_token {
+        req.headers_mut()
+            .append("X-Postmark-Account-Token", tok.try_into()?);
+    }
+
+    let base_url: url::Url = base_url.parse()?;
+
+    let url = match req.uri().path_and_query() {
+        Some(path) => base_url.join(path.as_str())?,
+        None => base_url,
+    };
+
+    *req.uri_mut() = url.as_str().parse()?;
+
+    Ok(req)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    enum TestError {
+        #[error(transparent)]
+        Header(#[from] http::header::InvalidHeaderValue),
+        #[error(transparent)]
+        Url(#[from] url::ParseError),
+        #[error(transparent)]
+        Uri(#[from] http::uri::InvalidUri),
+    }
+
+    fn prepare(token: Option<TokenKind>) -> Request<Bytes> {
+        let mut req = Request::get("/server").body(Bytes::new()).expect("request");
+        if let Some(token) = token {
+            req.extensions_mut().insert(EndpointInfo {
+                path_template: Cow::Borrowed("/server"),
+                token,
+                message_stream: None,
+            });
+        }
+
+        prepare_request::<TestError>(
+            req,
+            Some("server-token"),
+            Some("account-token"),
+            "https://api.postmarkapp.com",
+        )
+        .expect("prepare")
+    }
+
+    #[test]
+    fn attaches_only_the_token_of_the_endpoint_scope() {
+        let req = prepare(Some(TokenKind::Server));
+        assert_eq!(req.headers()["X-Postmark-Server-Token"], "server-token");
+        assert!(req.headers().get("X-Postmark-Account-Token").is_none());
+        assert_eq!(req.uri(), "https://api.postmarkapp.com/server");
+
+        let req = prepare(Some(TokenKind::Account));
+        assert!(req.headers().get("X-Postmark-Server-Token").is_none());
+        assert_eq!(req.headers()["X-Postmark-Account-Token"], "account-token");
+
+        let req = prepare(None);
+        assert_eq!(req.headers()["X-Postmark-Server-Token"], "server-token");
+        assert_eq!(req.headers()["X-Postmark-Account-Token"], "account-token");
+    }
+
+    #[test]
+    fn server_token_override_replaces_the_client_token() {
+        let mut req = Request::get("/server").body(Bytes::new()).expect("request");
+        ServerTokenOverride("tenant-token".into()).apply(&mut req);
+
+        let req = prepare_request::<TestError>(
+            req,
+            Some("server-token"),
+            None,
+            "https://api.postmarkapp.com",
+        )
+        .expect("prepare");
+
+        let tokens: Vec<_> = req
+            .headers()
+            .get_all("X-Postmark-Server-Token")
+            .iter()
+            .collect();
+        assert_eq!(tokens, ["tenant-token"]);
+    }
+}